@@ -1,23 +1,19 @@
 use std::env::current_dir;
-use std::fs::create_dir_all;
 
-use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+use cosmwasm_schema::{export_schema, schema_for, write_api};
 
-use rock_paper_scissors::msg::{
-    ExecuteMsg, GetGamesResponse, InstantiateMsg, MigrateMsg, QueryMsg,
-};
-use rock_paper_scissors::state::GameState;
+use rock_paper_scissors::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, ReceiveMsg};
 
 fn main() {
+    write_api! {
+        instantiate: InstantiateMsg,
+        execute: ExecuteMsg,
+        query: QueryMsg,
+        migrate: MigrateMsg,
+    }
+
+    //the cw20 hook isn't an entry point, so it's exported next to the combined api
     let mut out_dir = current_dir().unwrap();
     out_dir.push("schema");
-    create_dir_all(&out_dir).unwrap();
-    remove_schemas(&out_dir).unwrap();
-
-    export_schema(&schema_for!(InstantiateMsg), &out_dir);
-    export_schema(&schema_for!(ExecuteMsg), &out_dir);
-    export_schema(&schema_for!(QueryMsg), &out_dir);
-    export_schema(&schema_for!(MigrateMsg), &out_dir);
-    export_schema(&schema_for!(GameState), &out_dir);
-    export_schema(&schema_for!(GetGamesResponse), &out_dir);
+    export_schema(&schema_for!(ReceiveMsg), &out_dir);
 }
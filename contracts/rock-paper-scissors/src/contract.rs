@@ -1,32 +1,274 @@
+use std::convert::TryFrom;
+
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Order, Response,
-    StdError, StdResult, Uint128,
+    attr, from_binary, to_binary, Addr, Attribute, BankMsg, Binary, BlockInfo, Coin, CosmosMsg,
+    Deps, DepsMut, Empty, Env, Event, MessageInfo, Order, Response, StdError, StdResult, Storage,
+    Uint128, WasmMsg,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
+use cw20::{Balance, Cw20CoinVerified, Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::{Bound, PrimaryKey};
+use cw_utils::{Duration, Expiration, NativeBalance};
+use semver::Version;
+use sha2::{Digest, Sha256};
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, GetGamesResponse, InstantiateMsg, MigrateMsg, QueryMsg};
-use crate::state::{GameMove, GameResult, GameState, Leaderboard, ADMIN, GAMES, LEADERBOARD};
+use crate::events::{self, opponent_to_string, result_to_string, rule_set_to_string, Settlement};
+use crate::msg::{
+    AllowedDenomsResponse, ExecuteMsg, GetGamesResponse, GetHistoryResponse, GetRatingsResponse,
+    GetRuleSetsResponse, GetTopPlayersResponse, HouseResponse, InstantiateMsg, MigrateMsg,
+    OpenChallengeCursor, OpenChallengesResponse, OrderBy, PendingChallenge,
+    PendingChallengesResponse, PlayerStatsResponse, QueryMsg, RandomnessProviderMsg, ReceiveMsg,
+    RuleSetResponse, SeriesResponse, TopPlayersOrder,
+};
+use crate::state::{
+    games, player_denom_stats, player_stats, ratings, ChainTime, Config, DenomLimit, GameMove,
+    GameResult, GameState, GameStatus, HouseGame, PlayerDenomStats, PlayerRating, PlayerStats,
+    Round, RuleSet, Season, SeasonStatus, SettledGame, Standing, Tournament, TournamentStatus,
+    ADMIN, CONFIG, GAME_COUNT, GAME_HISTORY, HOUSE_BANKROLL, HOUSE_GAMES, LEGACY_GAMES,
+    LEGACY_LEADERBOARD, OPEN_CHALLENGES, PLAYER_HISTORY, RULE_SETS, SEASONS, SEASON_COUNT,
+    TOURNAMENTS, TOURNAMENT_COUNT,
+};
 
 const CONTRACT_NAME: &str = "crates.io:rock-paper-scissors";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// House fee can never be more than 10% of a pot
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// Rating of a player before their first settled game
+pub const INITIAL_RATING: u32 = 1200;
+
+/// Most rating points a player can win or lose in a single game
+const RATING_K_FACTOR: i64 = 32;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+//queries that filter an index read at most this many entries per page
+const MAX_SCAN: usize = 100;
+
+/// One day for each player to act when `StartGame` doesn't specify a timeout
+pub const DEFAULT_GAME_TIMEOUT: Duration = Duration::Time(24 * 60 * 60);
+
+/// Classic moves ordered so each one beats the next, wrapping around
+const CLASSIC_MOVES: [&str; 3] = ["rock", "scissors", "paper"];
+
+/// Rock-paper-scissors-lizard-Spock ordered so each move beats the next two, wrapping around
+const RPSLS_MOVES: [&str; 5] = ["rock", "scissors", "lizard", "paper", "spock"];
+
+/// Most moves a registered rule set can have
+pub const MAX_RULE_SET_MOVES: usize = 15;
+
+/// Largest bracket a tournament can have
+pub const MAX_TOURNAMENT_PARTICIPANTS: u32 = 64;
+
+/// Largest league a season can have, every pairing is scheduled when the season starts
+pub const MAX_SEASON_PARTICIPANTS: u32 = 16;
+
+/// League points for winning a match
+pub const POINTS_PER_WIN: u32 = 3;
+
+/// League points for a tied match
+pub const POINTS_PER_TIE: u32 = 1;
+
+/// Bets against the house are capped at 1% of its bankroll unless configured otherwise
+pub const DEFAULT_MAX_HOUSE_BET_BPS: u16 = 100;
+
+/// Bets against the house can never be more than 10% of its bankroll
+pub const MAX_HOUSE_BET_BPS: u16 = 1_000;
+
+/// Longest best-of series a game can be
+pub const MAX_BEST_OF: u32 = 9;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::new().add_attribute("method", "migrate"))
+pub fn migrate(mut deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::WrongContract {
+            name: stored.contract,
+        });
+    }
+
+    let version = parse_version(&stored.version)?;
+    if version > parse_version(CONTRACT_VERSION)? {
+        return Err(ContractError::CannotDowngrade {
+            version: stored.version,
+        });
+    }
+
+    //migrations run oldest first, each one brings the state up to the next layout
+    let mut res = Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", &stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION);
+
+    if version < Version::new(0, 2, 0) {
+        res = res.add_attributes(migrate_v0_1_0(deps.branch(), &env)?);
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(res)
+}
+
+fn parse_version(version: &str) -> StdResult<Version> {
+    version
+        .parse()
+        .map_err(|_| StdError::generic_err(format!("Invalid contract version: {}", version)))
+}
+
+/// v0.1.0 kept every game twice under `(host, opponent)` and `(opponent, host)`, left
+/// the mirror behind once a game was settled, had no config and kept a head-to-head
+/// leaderboard instead of per-player stats.
+fn migrate_v0_1_0(deps: DepsMut, env: &Env) -> StdResult<Vec<Attribute>> {
+    if CONFIG.may_load(deps.storage)?.is_none() {
+        let fee_collector = ADMIN
+            .get(deps.as_ref())?
+            .unwrap_or_else(|| env.contract.address.clone());
+        CONFIG.save(
+            deps.storage,
+            &Config {
+                fee_bps: 0,
+                fee_collector,
+                randomness_provider: None,
+                max_house_bet_bps: DEFAULT_MAX_HOUSE_BET_BPS,
+                paused: false,
+                paused_since: None,
+                paused_total: ChainTime::default(),
+                allowed_denoms: vec![],
+            },
+        )?;
+    }
+
+    let legacy_games = LEGACY_GAMES
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut migrated_games = 0u64;
+    for ((first, second), legacy) in legacy_games {
+        LEGACY_GAMES.remove(deps.storage, (&first, &second));
+
+        //mirrors are dropped, a mirror without its original is a settled game
+        if legacy.host != first || legacy.opponent != second || legacy.result.is_some() {
+            continue;
+        }
+
+        //the move was public in v0.1.0, so it's committed to with an empty salt
+        //and the host reveals it with `salt: ""`
+        let id = GAME_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+        GAME_COUNT.save(deps.storage, &id)?;
+
+        let game = GameState {
+            id,
+            status: GameStatus::AwaitingOpponent,
+            host: legacy.host,
+            opponent: Some(legacy.opponent),
+            host_wager: normalize_balance(Balance::from(legacy.host_wager)),
+            opp_wager: None,
+            host_move_commitment: hash_move(&legacy.host_move, ""),
+            host_move: None,
+            opp_move: None,
+            result: None,
+            rule_set: RuleSet::Classic,
+            tournament_id: None,
+            season_id: None,
+            best_of: 1,
+            rounds: vec![],
+            host_score: 0,
+            opp_score: 0,
+            timeout: DEFAULT_GAME_TIMEOUT,
+            expires: DEFAULT_GAME_TIMEOUT.after(&env.block),
+            paused_offset: ChainTime::default(),
+        };
+        games().save(deps.storage, id, &game)?;
+        migrated_games += 1;
+    }
+
+    let leaderboard = LEGACY_LEADERBOARD
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut migrated_records = 0u64;
+    for ((host, opponent), record) in leaderboard {
+        LEGACY_LEADERBOARD.remove(deps.storage, (&host, &opponent));
+
+        let host_wins = legacy_score(record.host_score)?;
+        let opp_wins = legacy_score(record.opp_score)?;
+        let ties = legacy_score(record.ties)?;
+
+        for (player, wins, losses) in [
+            (record.host, host_wins, opp_wins),
+            (record.opponent, opp_wins, host_wins),
+        ] {
+            let mut stats = player_stats()
+                .may_load(deps.storage, player.clone())?
+                .unwrap_or_else(|| PlayerStats::new(player.clone()));
+            stats.wins += wins;
+            stats.losses += losses;
+            stats.ties += ties;
+            player_stats().save(deps.storage, player, &stats)?;
+        }
+        migrated_records += 1;
+    }
+
+    Ok(vec![
+        attr("migrated_games", migrated_games.to_string()),
+        attr("migrated_leaderboard_records", migrated_records.to_string()),
+    ])
+}
+
+fn legacy_score(score: Option<Uint128>) -> StdResult<u64> {
+    u64::try_from(score.unwrap_or_default().u128())
+        .map_err(|_| StdError::generic_err("Legacy leaderboard score does not fit in u64"))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    if msg.fee_bps > MAX_FEE_BPS {
+        return Err(ContractError::InvalidFee { max: MAX_FEE_BPS });
+    }
+
+    let max_house_bet_bps = msg.max_house_bet_bps.unwrap_or(DEFAULT_MAX_HOUSE_BET_BPS);
+    if max_house_bet_bps > MAX_HOUSE_BET_BPS {
+        return Err(ContractError::BetTooLarge {
+            max_bps: MAX_HOUSE_BET_BPS,
+        });
+    }
+
+    //fees go to the instantiator unless a collector is given
+    let fee_collector = match msg.fee_collector {
+        Some(fee_collector) => deps.api.addr_validate(&fee_collector)?,
+        None => info.sender,
+    };
+
+    let randomness_provider = msg
+        .randomness_provider
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            fee_bps: msg.fee_bps,
+            fee_collector,
+            randomness_provider,
+            max_house_bet_bps,
+            paused: false,
+            paused_since: None,
+            paused_total: ChainTime::default(),
+            allowed_denoms: vec![],
+        },
+    )?;
+
     match msg.admin {
         Some(admin) => {
             let valid_addr = deps.api.addr_validate(&admin)?;
@@ -47,363 +289,2783 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::StartGame {
             opponent,
-            host_move,
-        } => try_start_game(deps, info, opponent, host_move),
-        ExecuteMsg::OpponentResponse { opp_move, host } => {
-            try_opponent_response(deps, info, host, opp_move)
+            host_move_commitment,
+            timeout,
+            best_of,
+            rule_set,
+        } => try_start_game(
+            deps,
+            env,
+            info.sender,
+            Balance::from(info.funds),
+            opponent,
+            host_move_commitment,
+            timeout,
+            best_of,
+            rule_set,
+        ),
+        ExecuteMsg::OpponentResponse { game_id, opp_move } => try_opponent_response(
+            deps,
+            env,
+            info.sender,
+            Balance::from(info.funds),
+            game_id,
+            opp_move,
+        ),
+        ExecuteMsg::Receive(msg) => try_receive(deps, env, info, msg),
+        ExecuteMsg::Pause {} => try_set_paused(deps, env, info, true),
+        ExecuteMsg::Unpause {} => try_set_paused(deps, env, info, false),
+        ExecuteMsg::UpdateAdmin { admin } => try_update_admin(deps, info, admin),
+        ExecuteMsg::UpdateConfig {
+            fee_bps,
+            fee_collector,
+            randomness_provider,
+            max_house_bet_bps,
+            allowed_denoms,
+        } => try_update_config(
+            deps,
+            info,
+            fee_bps,
+            fee_collector,
+            randomness_provider,
+            max_house_bet_bps,
+            allowed_denoms,
+        ),
+        ExecuteMsg::ClearRandomnessProvider {} => try_clear_randomness_provider(deps, info),
+        ExecuteMsg::PlayHouse {
+            player_move,
+            rule_set,
+        } => try_play_house(deps, env, info, player_move, rule_set),
+        ExecuteMsg::ReceiveRandomness { job_id, randomness } => {
+            try_receive_randomness(deps, info, job_id, randomness)
+        }
+        ExecuteMsg::FundHouse {} => try_fund_house(deps, info),
+        ExecuteMsg::WithdrawHouse { amount } => try_withdraw_house(deps, info, amount),
+        ExecuteMsg::RegisterRuleSet { name, moves } => {
+            try_register_rule_set(deps, info, name, moves)
         }
+        ExecuteMsg::CreateTournament {
+            entry_fee,
+            max_participants,
+            payouts_bps,
+            rule_set,
+            timeout,
+        } => try_create_tournament(
+            deps,
+            info,
+            entry_fee,
+            max_participants,
+            payouts_bps,
+            rule_set,
+            timeout,
+        ),
+        ExecuteMsg::JoinTournament { tournament_id } => {
+            try_join_tournament(deps, env, info, tournament_id)
+        }
+        ExecuteMsg::StartTournament { tournament_id } => {
+            try_start_tournament(deps, env, info, tournament_id)
+        }
+        ExecuteMsg::CancelTournament { tournament_id } => {
+            try_cancel_tournament(deps, info, tournament_id)
+        }
+        ExecuteMsg::CreateSeason {
+            max_participants,
+            payouts_bps,
+            rule_set,
+            timeout,
+        } => try_create_season(deps, info, max_participants, payouts_bps, rule_set, timeout),
+        ExecuteMsg::JoinSeason { season_id } => try_join_season(deps, info, season_id),
+        ExecuteMsg::FundSeason { season_id } => try_fund_season(deps, info, season_id),
+        ExecuteMsg::StartSeason { season_id } => try_start_season(deps, env, info, season_id),
+        ExecuteMsg::FinalizeSeason { season_id } => try_finalize_season(deps, info, season_id),
+        ExecuteMsg::RevealMove {
+            game_id,
+            host_move,
+            salt,
+        } => try_reveal_move(deps, env, info, game_id, host_move, salt),
+        ExecuteMsg::CommitMove {
+            game_id,
+            host_move_commitment,
+        } => try_commit_move(deps, env, info, game_id, host_move_commitment),
+        ExecuteMsg::CancelGame { game_id } => try_cancel_game(deps, info, game_id),
+        ExecuteMsg::ClaimTimeout { game_id } => try_claim_timeout(deps, env, game_id),
     }
 }
 
-pub fn try_start_game(
+/// Entry point for cw20 wagers, the token contract is `info.sender` and the
+/// player who sent the tokens is `wrapper.sender`.
+pub fn try_receive(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    opponent: String,
-    host_move: GameMove,
+    wrapper: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
-    if info.sender.clone().to_string() == opponent.clone() {
-        return Err(ContractError::HostAndOpponentCannotBeTheSame {
-            val: opponent.clone(),
-        });
-    }
-    //validate opp address
-    let valid_addr = deps.api.addr_validate(&opponent.clone())?;
+    let sender = deps.api.addr_validate(&wrapper.sender)?;
+    let wager = Balance::Cw20(Cw20CoinVerified {
+        address: info.sender,
+        amount: wrapper.amount,
+    });
 
-    //check if game already exists between the to addresses
-    let host = info.sender.clone().to_string();
-    match GAMES.load(deps.storage, (&host, &opponent)) {
-        Ok(_) => return Err(ContractError::ActiveGameAlreadyExists {}),
-        Err(_) => {}
+    match from_binary(&wrapper.msg)? {
+        ReceiveMsg::StartGame {
+            opponent,
+            host_move_commitment,
+            timeout,
+            best_of,
+            rule_set,
+        } => try_start_game(
+            deps,
+            env,
+            sender,
+            wager,
+            opponent,
+            host_move_commitment,
+            timeout,
+            best_of,
+            rule_set,
+        ),
+        ReceiveMsg::OpponentResponse { game_id, opp_move } => {
+            try_opponent_response(deps, env, sender, wager, game_id, opp_move)
+        }
     }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn try_start_game(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    wager: Balance,
+    opponent: Option<String>,
+    host_move_commitment: String,
+    timeout: Option<Duration>,
+    best_of: Option<u32>,
+    rule_set: Option<RuleSet>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+
+    //no opponent means an open challenge the first matching deposit accepts
+    let valid_addr = match opponent {
+        Some(opponent) => {
+            if sender == opponent {
+                return Err(ContractError::HostAndOpponentCannotBeTheSame { val: opponent });
+            }
+            //validate opp address
+            Some(deps.api.addr_validate(&opponent)?)
+        }
+        None => None,
+    };
 
     //validate funds have been sent
-    if info.clone().funds.len() == 0 {
+    let host_wager = normalize_balance(wager);
+    if host_wager.is_empty() {
         return Err(ContractError::MissingWagerAmount {});
     };
 
+    let config = CONFIG.load(deps.storage)?;
+    assert_wager_allowed(&config, &host_wager)?;
+
+    let host_move_commitment = validate_commitment(host_move_commitment)?;
+
+    let timeout = timeout.unwrap_or(DEFAULT_GAME_TIMEOUT);
+    if timeout == Duration::Height(0) || timeout == Duration::Time(0) {
+        return Err(ContractError::InvalidTimeout {});
+    }
+
+    //an even series could end level, so every series needs an odd number of rounds
+    let best_of = best_of.unwrap_or(1);
+    if best_of % 2 == 0 || best_of > MAX_BEST_OF {
+        return Err(ContractError::InvalidBestOf { max: MAX_BEST_OF });
+    }
+
+    let rule_set = rule_set.unwrap_or(RuleSet::Classic);
+    rule_set_moves(deps.storage, &rule_set)?;
+
+    let id = GAME_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    GAME_COUNT.save(deps.storage, &id)?;
+
     let game = GameState {
-        host: Addr::unchecked(info.sender.clone()),
-        opponent: valid_addr.clone(),
-        host_wager: info.funds.clone(),
+        id,
+        status: GameStatus::AwaitingOpponent,
+        host: sender.clone(),
+        opponent: valid_addr,
+        host_wager: host_wager.clone(),
         opp_wager: None,
-        host_move: host_move.clone(),
+        host_move_commitment,
+        host_move: None,
         opp_move: None,
         result: None,
+        rule_set,
+        tournament_id: None,
+        season_id: None,
+        best_of,
+        rounds: vec![],
+        host_score: 0,
+        opp_score: 0,
+        timeout,
+        expires: timeout.after(&env.block),
+        paused_offset: paused_total(&config, &env.block),
     };
 
-    GAMES.save(
-        deps.storage,
-        (
-            &info.sender.clone().to_string(),
-            &valid_addr.clone().to_string(),
-        ),
-        &game,
-    )?;
-    GAMES.save(
-        deps.storage,
-        (
-            &valid_addr.clone().to_string(),
-            &info.sender.clone().to_string(),
-        ),
-        &game,
-    )?;
-
-    let mut host_wager = String::new();
-
-    for coin in info.clone().funds.iter() {
-        let coin_amount = coin.clone().amount.to_string();
-        let coin_denom = coin.clone().denom;
-        host_wager = host_wager + &coin_amount + &coin_denom + " ";
+    games().save(deps.storage, id, &game)?;
+    if game.opponent.is_none() {
+        for (denom, amount) in wager_denoms(&game.host_wager) {
+            OPEN_CHALLENGES.save(deps.storage, (&denom, amount.u128(), id), &Empty {})?;
+        }
     }
 
     Ok(Response::new()
         .add_attribute("execute", "start_game")
-        .add_attribute("host", info.sender)
-        .add_attribute("opponent", valid_addr)
-        .add_attribute("host_wager", host_wager))
+        .add_attribute("game_id", id.to_string())
+        .add_attribute("host", sender)
+        .add_attribute("opponent", opponent_to_string(&game.opponent))
+        .add_attribute("host_wager", wager_to_string(&host_wager))
+        .add_attribute("expires", game.expires.to_string())
+        .add_event(events::game_started(&game)))
 }
 
 pub fn try_opponent_response(
     deps: DepsMut,
-    info: MessageInfo,
-    host: String,
+    env: Env,
+    sender: Addr,
+    wager: Balance,
+    game_id: u64,
     opp_move: GameMove,
 ) -> Result<Response, ContractError> {
-    let valid_host = deps.api.addr_validate(&host.clone())?;
+    assert_not_paused(deps.storage)?;
 
-    match GAMES.load(deps.storage, (&host, &info.sender.to_string())) {
-        Ok(game) => {
-            if info.funds.clone() != game.host_wager.clone() {
-                return Err(ContractError::InsufficientWagerAmount {});
-            }
+    let mut game = load_game(deps.storage, game_id)?;
 
-            let result = get_game_result(&game.host_move, &opp_move)?;
-
-            let result_of_game: String;
-
-            if let prev_leadboard = LEADERBOARD.load(
-                deps.storage,
-                (
-                    &game.host.clone().to_string(),
-                    &game.opponent.clone().to_string(),
-                ),
-            )? {
-                match result {
-                    GameResult::HostWins => {
-                        result_of_game = "host_wins".to_string();
-
-                        let new_host_score = match prev_leadboard.host_score {
-                            Some(h_score) => h_score.checked_add(Uint128::new(1)).unwrap(),
-                            None => Uint128::new(1),
-                        };
-
-                        let new_leaderboard = Leaderboard {
-                            host: prev_leadboard.host.clone(),
-                            opponent: prev_leadboard.opponent.clone(),
-                            host_score: Some(new_host_score.clone()),
-                            opp_score: prev_leadboard.opp_score.clone(),
-                            ties: prev_leadboard.ties.clone(),
-                        };
-
-                        LEADERBOARD.save(
-                            deps.storage,
-                            (
-                                &new_leaderboard.host.to_string(),
-                                &new_leaderboard.opponent.to_string(),
-                            ),
-                            &new_leaderboard,
-                        )?;
-                    }
-                    GameResult::OpponentWins => {
-                        result_of_game = "opponent_wins".to_string();
-
-                        let new_opp_score = match prev_leadboard.opp_score {
-                            Some(o_score) => o_score.checked_add(Uint128::new(1)).unwrap(),
-                            None => Uint128::new(1),
-                        };
-
-                        let new_leaderboard = Leaderboard {
-                            host: prev_leadboard.host.clone(),
-                            opponent: prev_leadboard.opponent.clone(),
-                            host_score: prev_leadboard.host_score.clone(),
-                            opp_score: Some(new_opp_score.clone()),
-                            ties: prev_leadboard.ties.clone(),
-                        };
-
-                        LEADERBOARD.save(
-                            deps.storage,
-                            (
-                                &new_leaderboard.host.to_string(),
-                                &new_leaderboard.opponent.to_string(),
-                            ),
-                            &new_leaderboard,
-                        )?;
-                    }
-                    GameResult::Tie => {
-                        result_of_game = "tie".to_string();
-
-                        let new_ties_score = match prev_leadboard.ties {
-                            Some(ties_score) => ties_score.checked_add(Uint128::new(1)).unwrap(),
-                            None => Uint128::new(1),
-                        };
-
-                        let new_leaderboard = Leaderboard {
-                            host: prev_leadboard.host.clone(),
-                            opponent: prev_leadboard.opponent.clone(),
-                            host_score: prev_leadboard.host_score.clone(),
-                            opp_score: prev_leadboard.opp_score.clone(),
-                            ties: Some(new_ties_score.clone()),
-                        };
-
-                        LEADERBOARD.save(
-                            deps.storage,
-                            (
-                                &new_leaderboard.host.to_string(),
-                                &new_leaderboard.opponent.to_string(),
-                            ),
-                            &new_leaderboard,
-                        )?;
-                    }
-                }
-            };
+    match game.status {
+        GameStatus::AwaitingOpponent => {}
+        GameStatus::AwaitingReveal => return Err(ContractError::OpponentAlreadyResponded {}),
+        GameStatus::AwaitingCommitment => return Err(ContractError::HostHasNotCommitted {}),
+    }
 
-            /* match result {
-                GameResult::HostWins => {
-                    game_result = "host_wins".to_string();
-                    leaderboard = Leaderboard {
-                        host: game.host.clone(),
-                        opponent: game.opponent.clone(),
-                        host_score: Some(1),
-                        opp_score: todo!(),
-                        ties: todo!(),
-                    }
-                }
-                GameResult::OpponentWins => game_result = "opponent_wins".to_string(),
-                GameResult::Tie => game_result = "tie".to_string(),
-            } */
-
-            let bank_msg = send_funds_to_winner(
-                result.clone(),
-                info.funds.clone(),
-                game.host_wager.clone(),
-                valid_host,
-                info.sender.clone(),
-            )?;
-
-            let new_game = GameState {
-                host: game.host.clone(),
-                opponent: game.opponent.clone(),
-                host_wager: game.host_wager.clone(),
-                opp_wager: Some(info.funds.clone()),
-                host_move: game.host_move.clone(),
-                opp_move: Some(opp_move.clone()),
-                result: Some(result),
-            };
+    match &game.opponent {
+        Some(opponent) if opponent != &sender => {
+            return Err(ContractError::GameNotFound {});
+        }
+        None if game.host == sender => {
+            return Err(ContractError::HostAndOpponentCannotBeTheSame {
+                val: sender.to_string(),
+            });
+        }
+        _ => {}
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    if game_expires(&config, &env.block, &game).is_expired(&env.block) {
+        return Err(ContractError::GameExpired {});
+    }
 
-            GAMES.save(
-                deps.storage,
-                (&host.clone(), &info.sender.clone().to_string()),
-                &new_game,
-            )?;
+    let moves = rule_set_moves(deps.storage, &game.rule_set)?;
+    if !moves.iter().any(|m| m == opp_move.as_str()) {
+        return Err(ContractError::InvalidMove {});
+    }
+
+    let opp_wager = normalize_balance(wager);
+    match game.opp_wager {
+        //later rounds of a series are played for the wager escrowed in the first one
+        Some(_) if !opp_wager.is_empty() => {
+            return Err(ContractError::WagerAlreadyEscrowed {});
+        }
+        Some(_) => {}
+        //every denom of the host's wager has to be matched exactly, in the same token type
+        None if opp_wager != game.host_wager => {
+            return Err(ContractError::InsufficientWagerAmount {});
+        }
+        None => game.opp_wager = Some(opp_wager),
+    }
+
+    //first player to match the wager takes the open challenge
+    if game.opponent.is_none() {
+        remove_open_challenge(deps.storage, &game);
+        game.opponent = Some(sender.clone());
+    }
 
-            GAMES.remove(
-                deps.storage,
-                (&host.clone(), &info.sender.to_string().clone()),
-            );
+    //the host's move stays hidden until they reveal it with try_reveal_move
+    game.status = GameStatus::AwaitingReveal;
+    game.opp_move = Some(opp_move);
+    restart_clock(&mut game, &config, &env.block);
 
-            Ok(Response::new()
-                .add_attribute("execute", "opponent_response")
-                .add_attribute("host", host)
-                .add_attribute("opponent", info.sender.to_string())
-                //.add_attribute("game_result", result_of_game)
-                .add_messages(bank_msg))
+    games().save(deps.storage, game_id, &game)?;
+    let responded = events::game_responded(&game);
+
+    Ok(Response::new()
+        .add_attribute("execute", "opponent_response")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("host", game.host)
+        .add_attribute("opponent", sender)
+        .add_attribute("expires", game.expires.to_string())
+        .add_event(responded))
+}
+
+pub fn try_reveal_move(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    game_id: u64,
+    host_move: GameMove,
+    salt: String,
+) -> Result<Response, ContractError> {
+    let mut game = load_game(deps.storage, game_id)?;
+
+    if game.host != info.sender {
+        return Err(ContractError::GameNotFound {});
+    }
+
+    let (opponent, opp_move) = match (game.opponent.clone(), game.opp_move.clone()) {
+        (Some(opponent), Some(opp_move)) => (opponent, opp_move),
+        _ => return Err(ContractError::OpponentHasNotResponded {}),
+    };
+
+    let config = CONFIG.load(deps.storage)?;
+    if game_expires(&config, &env.block, &game).is_expired(&env.block) {
+        return Err(ContractError::GameExpired {});
+    }
+
+    if hash_move(&host_move, &salt) != game.host_move_commitment {
+        return Err(ContractError::InvalidReveal {});
+    }
+
+    let moves = rule_set_moves(deps.storage, &game.rule_set)?;
+    let mut result = get_game_result(&moves, &host_move, &opp_move)?;
+    let mut round_settled = None;
+
+    //series and bracket matches replay ties until someone wins
+    if game.best_of > 1 || game.tournament_id.is_some() {
+        match result {
+            GameResult::HostWins => game.host_score += 1,
+            GameResult::OpponentWins => game.opp_score += 1,
+            GameResult::Tie => {}
+        }
+        game.rounds.push(Round {
+            host_move: host_move.clone(),
+            opp_move,
+            result: result.clone(),
+        });
+
+        //nobody has a majority yet, the host commits to a move for the next round
+        let rounds_to_win = game.best_of / 2 + 1;
+        if game.host_score < rounds_to_win && game.opp_score < rounds_to_win {
+            game.status = GameStatus::AwaitingCommitment;
+            game.host_move_commitment = String::new();
+            game.opp_move = None;
+            restart_clock(&mut game, &config, &env.block);
+            games().save(deps.storage, game_id, &game)?;
+            let round_settled = events::round_settled(&game, Some(game.expires));
+
+            return Ok(Response::new()
+                .add_attribute("execute", "reveal_move")
+                .add_attribute("game_id", game_id.to_string())
+                .add_attribute("host", info.sender)
+                .add_attribute("opponent", opponent)
+                .add_attribute("round_result", result_to_string(&result))
+                .add_attribute("host_score", game.host_score.to_string())
+                .add_attribute("opp_score", game.opp_score.to_string())
+                .add_attribute("expires", game.expires.to_string())
+                .add_event(round_settled));
         }
-        Err(_) => return Err(ContractError::GameNotFound {}),
+        round_settled = Some(events::round_settled(&game, None));
+
+        result = if game.host_score >= rounds_to_win {
+            GameResult::HostWins
+        } else {
+            GameResult::OpponentWins
+        };
     }
+
+    game.host_move = Some(host_move);
+    game.result = Some(result.clone());
+
+    let (bank_msg, fee, settled_events) =
+        settle_game(deps.storage, &env, &game, &opponent, &result, &config)?;
+
+    games().remove(deps.storage, game_id)?;
+    archive_game(deps.storage, &env, &game)?;
+
+    Ok(Response::new()
+        .add_attribute("execute", "reveal_move")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("host", info.sender)
+        .add_attribute("opponent", opponent)
+        .add_attribute("game_result", result_to_string(&result))
+        .add_attributes(fee_attributes(&fee, &config))
+        .add_events(round_settled)
+        .add_events(settled_events)
+        .add_messages(bank_msg))
 }
 
-pub fn get_game_result(host_move: &GameMove, opp_move: &GameMove) -> StdResult<GameResult> {
-    if &host_move == &opp_move {
-        Ok(GameResult::Tie)
-    } else if host_move == &GameMove::Rock && opp_move == &GameMove::Paper
-        || host_move == &GameMove::Paper && opp_move == &GameMove::Scissors
-        || host_move == &GameMove::Scissors && opp_move == &GameMove::Rock
-    {
-        Ok(GameResult::OpponentWins)
-    } else {
-        Ok(GameResult::HostWins)
+pub fn try_commit_move(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    game_id: u64,
+    host_move_commitment: String,
+) -> Result<Response, ContractError> {
+    let mut game = load_game(deps.storage, game_id)?;
+
+    if game.host != info.sender {
+        return Err(ContractError::GameNotFound {});
+    }
+
+    if game.status != GameStatus::AwaitingCommitment {
+        return Err(ContractError::HostAlreadyCommitted {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    if game_expires(&config, &env.block, &game).is_expired(&env.block) {
+        return Err(ContractError::GameExpired {});
     }
+
+    game.host_move_commitment = validate_commitment(host_move_commitment)?;
+    game.status = GameStatus::AwaitingOpponent;
+    restart_clock(&mut game, &config, &env.block);
+
+    games().save(deps.storage, game_id, &game)?;
+
+    Ok(Response::new()
+        .add_attribute("execute", "commit_move")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("host", info.sender)
+        .add_attribute("opponent", opponent_to_string(&game.opponent))
+        .add_attribute("round", (game.rounds.len() + 1).to_string())
+        .add_attribute("expires", game.expires.to_string())
+        .add_event(events::move_committed(&game)))
 }
 
-pub fn send_funds_to_winner(
-    result: GameResult,
-    opp_wager: Vec<Coin>,
-    host_wager: Vec<Coin>,
-    host: Addr,
-    opponent: Addr,
-) -> StdResult<Vec<BankMsg>> {
-    let mut bank_msgs: Vec<BankMsg> = vec![];
+pub fn try_cancel_game(
+    deps: DepsMut,
+    info: MessageInfo,
+    game_id: u64,
+) -> Result<Response, ContractError> {
+    let game = load_game(deps.storage, game_id)?;
+
+    if game.host != info.sender {
+        return Err(ContractError::GameNotFound {});
+    }
 
-    let mut msg: BankMsg;
+    //once the opponent has matched the wager the host can only play on or forfeit
+    if game.opp_wager.is_some() {
+        return Err(ContractError::OpponentAlreadyResponded {});
+    }
 
-    if result == GameResult::Tie {
-        msg = BankMsg::Send {
-            to_address: host.clone().to_string(),
-            amount: host_wager.clone(),
-        };
+    games().remove(deps.storage, game_id)?;
+    remove_open_challenge(deps.storage, &game);
 
-        bank_msgs.push(msg);
+    Ok(Response::new()
+        .add_attribute("execute", "cancel_game")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("host", info.sender)
+        .add_attribute("opponent", opponent_to_string(&game.opponent))
+        .add_event(events::game_cancelled(&game, "cancelled"))
+        .add_messages(transfer_msgs(&game.host, game.host_wager)?))
+}
 
-        let msg_1 = BankMsg::Send {
-            to_address: opponent.clone().to_string(),
-            amount: opp_wager.clone(),
-        };
-        bank_msgs.push(msg_1);
+pub fn try_claim_timeout(deps: DepsMut, env: Env, game_id: u64) -> Result<Response, ContractError> {
+    if let Some(game) = HOUSE_GAMES.may_load(deps.storage, game_id)? {
+        return try_claim_house_timeout(deps, env, game);
+    }
+
+    let mut game = load_game(deps.storage, game_id)?;
+
+    //the clock stands still while paused, so nobody forfeits for being locked out
+    let config = CONFIG.load(deps.storage)?;
+    if !game_expires(&config, &env.block, &game).is_expired(&env.block) {
+        return Err(ContractError::GameNotExpired {});
+    }
+
+    let (bank_msg, fee, events) = match (game.opponent.clone(), game.opp_wager.clone()) {
+        (Some(opponent), Some(_)) => {
+            let result = match game.status {
+                //the opponent walked away partway through a series
+                GameStatus::AwaitingOpponent => GameResult::HostWins,
+                //the host never revealed or committed, so they forfeit the pot
+                _ => GameResult::OpponentWins,
+            };
+            game.result = Some(result.clone());
+            settle_game(deps.storage, &env, &game, &opponent, &result, &config)?
+        }
+        //nobody took up the challenge, the host gets their wager back
+        _ => (
+            transfer_msgs(&game.host, game.host_wager.clone())?,
+            Balance::default(),
+            vec![events::game_cancelled(&game, "expired")],
+        ),
     };
 
-    let total_wager = host_wager[0]
-        .clone()
-        .amount
-        .checked_add(opp_wager[0].amount.clone())?;
-
-    if result == GameResult::OpponentWins {
-        msg = BankMsg::Send {
-            to_address: opponent.to_string(),
-            amount: vec![Coin {
-                amount: total_wager,
-                denom: host_wager[0].denom.clone(),
-            }],
-        };
-        bank_msgs.push(msg);
-    } else if result == GameResult::HostWins {
-        msg = BankMsg::Send {
-            to_address: host.to_string(),
-            amount: vec![Coin {
-                amount: total_wager,
-                denom: host_wager[0].denom.clone(),
-            }],
-        };
-        bank_msgs.push(msg);
+    games().remove(deps.storage, game_id)?;
+    remove_open_challenge(deps.storage, &game);
+    if game.result.is_some() {
+        archive_game(deps.storage, &env, &game)?;
     }
 
-    Ok(bank_msgs)
+    let result = match &game.result {
+        Some(result) => result_to_string(result),
+        None => "refund",
+    };
+
+    Ok(Response::new()
+        .add_attribute("execute", "claim_timeout")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("host", game.host)
+        .add_attribute("opponent", opponent_to_string(&game.opponent))
+        .add_attribute("game_result", result)
+        .add_attributes(fee_attributes(&fee, &config))
+        .add_events(events)
+        .add_messages(bank_msg))
 }
 
-/* pub fn get_prev_leaderboard(host: Addr, opponent: Addr) -> StdResult<Leaderboard> {
-    match LEADERBOARD.load(de, k)
-} */
+#[allow(clippy::too_many_arguments)]
+pub fn try_create_tournament(
+    deps: DepsMut,
+    info: MessageInfo,
+    entry_fee: Coin,
+    max_participants: u32,
+    payouts_bps: Vec<u16>,
+    rule_set: Option<RuleSet>,
+    timeout: Option<Duration>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::GetGameByHostAndOpponent { host, opponent } => {
-            to_binary(&try_query_game_by_host_and_opponent(deps, host, opponent)?)
-        }
-        QueryMsg::GetGamesByHost { host } => to_binary(&try_query_games_by_host(deps, host)?),
+    if entry_fee.amount.is_zero() || !(2..=MAX_TOURNAMENT_PARTICIPANTS).contains(&max_participants)
+    {
+        return Err(ContractError::InvalidTournament {
+            max: MAX_TOURNAMENT_PARTICIPANTS,
+        });
+    }
+
+    if payouts_bps.is_empty()
+        || payouts_bps.len() > max_participants as usize
+        || payouts_bps.iter().map(|bps| *bps as u64).sum::<u64>() != 10_000
+    {
+        return Err(ContractError::InvalidPayouts {});
+    }
+
+    let rule_set = rule_set.unwrap_or(RuleSet::Classic);
+    rule_set_moves(deps.storage, &rule_set)?;
+
+    let timeout = timeout.unwrap_or(DEFAULT_GAME_TIMEOUT);
+    if timeout == Duration::Height(0) || timeout == Duration::Time(0) {
+        return Err(ContractError::InvalidTimeout {});
     }
+
+    let id = TOURNAMENT_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    TOURNAMENT_COUNT.save(deps.storage, &id)?;
+
+    let tournament = Tournament {
+        id,
+        organizer: info.sender.clone(),
+        status: TournamentStatus::Registration,
+        entry_fee: entry_fee.clone(),
+        max_participants,
+        payouts_bps,
+        rule_set,
+        timeout,
+        participants: vec![],
+        round: 0,
+        matches: vec![],
+        advanced: vec![],
+        eliminated: vec![],
+        champion: None,
+    };
+    TOURNAMENTS.save(deps.storage, id, &tournament)?;
+
+    Ok(Response::new()
+        .add_attribute("execute", "create_tournament")
+        .add_attribute("tournament_id", id.to_string())
+        .add_attribute("organizer", info.sender)
+        .add_attribute("entry_fee", entry_fee.to_string())
+        .add_attribute("max_participants", max_participants.to_string()))
 }
 
-pub fn try_query_game_by_host_and_opponent(
-    deps: Deps,
-    host: String,
-    opponent: String,
-) -> StdResult<GetGamesResponse> {
-    let _valid_host = deps.clone().api.addr_validate(&host.clone())?;
-    let _valid_opp = deps.clone().api.addr_validate(&opponent.clone())?;
-    let mut game: Vec<GameState> = vec![];
+pub fn try_join_tournament(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tournament_id: u64,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
 
-    match GAMES.load(deps.storage, (&host.clone(), &opponent.clone())) {
-        Ok(g) => {
-            game.push(g);
+    let mut tournament = load_tournament(deps.storage, tournament_id)?;
 
-            Ok(GetGamesResponse { games: game })
-        }
-        Err(_) => Err(StdError::generic_err("No game found")),
+    if tournament.status != TournamentStatus::Registration {
+        return Err(ContractError::TournamentStarted {});
     }
+
+    if tournament.participants.contains(&info.sender) {
+        return Err(ContractError::AlreadyRegistered {});
+    }
+
+    if normalize_wager(info.funds) != vec![tournament.entry_fee.clone()] {
+        return Err(ContractError::InsufficientWagerAmount {});
+    }
+
+    tournament.participants.push(info.sender.clone());
+
+    //a full bracket starts straight away
+    let mut res = Response::new()
+        .add_attribute("execute", "join_tournament")
+        .add_attribute("tournament_id", tournament_id.to_string())
+        .add_attribute("player", info.sender);
+    if tournament.participants.len() == tournament.max_participants as usize {
+        let players = tournament.participants.clone();
+        let (msgs, events) = start_tournament_round(deps.storage, &env, &mut tournament, players)?;
+        res = res.add_messages(msgs).add_events(events);
+    }
+
+    TOURNAMENTS.save(deps.storage, tournament_id, &tournament)?;
+
+    Ok(res)
 }
 
-pub fn try_query_games_by_host(deps: Deps, host: String) -> StdResult<GetGamesResponse> {
-    let _valid_host = deps.clone().api.addr_validate(&host.clone())?;
+pub fn try_start_tournament(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tournament_id: u64,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
 
-    let res: StdResult<Vec<_>> = GAMES
-        .prefix(&host)
-        .range(deps.storage, None, None, Order::Ascending)
-        .collect();
+    let mut tournament = load_tournament(deps.storage, tournament_id)?;
 
-    let games_found = res?;
+    if tournament.organizer != info.sender {
+        return Err(ContractError::NotOrganizer {});
+    }
 
-    let mut games: Vec<GameState> = vec![];
+    if tournament.status != TournamentStatus::Registration {
+        return Err(ContractError::TournamentStarted {});
+    }
 
-    for game in games_found {
-        games.push(game.1);
+    if tournament.participants.len() < 2 {
+        return Err(ContractError::NotEnoughParticipants {});
     }
 
-    Ok(GetGamesResponse { games })
+    let players = tournament.participants.clone();
+    let (msgs, events) = start_tournament_round(deps.storage, &env, &mut tournament, players)?;
+    TOURNAMENTS.save(deps.storage, tournament_id, &tournament)?;
+
+    Ok(Response::new()
+        .add_attribute("execute", "start_tournament")
+        .add_attribute("tournament_id", tournament_id.to_string())
+        .add_attribute("participants", tournament.participants.len().to_string())
+        .add_events(events)
+        .add_messages(msgs))
+}
+
+pub fn try_cancel_tournament(
+    deps: DepsMut,
+    info: MessageInfo,
+    tournament_id: u64,
+) -> Result<Response, ContractError> {
+    let tournament = load_tournament(deps.storage, tournament_id)?;
+
+    if tournament.organizer != info.sender {
+        return Err(ContractError::NotOrganizer {});
+    }
+
+    if tournament.status != TournamentStatus::Registration {
+        return Err(ContractError::TournamentStarted {});
+    }
+
+    TOURNAMENTS.remove(deps.storage, tournament_id);
+
+    let refunds = tournament.participants.iter().map(|player| BankMsg::Send {
+        to_address: player.to_string(),
+        amount: vec![tournament.entry_fee.clone()],
+    });
+
+    Ok(Response::new()
+        .add_attribute("execute", "cancel_tournament")
+        .add_attribute("tournament_id", tournament_id.to_string())
+        .add_messages(refunds))
+}
+
+pub fn try_create_season(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_participants: u32,
+    payouts_bps: Vec<u16>,
+    rule_set: Option<RuleSet>,
+    timeout: Option<Duration>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    if !(2..=MAX_SEASON_PARTICIPANTS).contains(&max_participants) {
+        return Err(ContractError::InvalidSeason {
+            max: MAX_SEASON_PARTICIPANTS,
+        });
+    }
+
+    if payouts_bps.is_empty()
+        || payouts_bps.len() > max_participants as usize
+        || payouts_bps.iter().map(|bps| *bps as u64).sum::<u64>() != 10_000
+    {
+        return Err(ContractError::InvalidPayouts {});
+    }
+
+    let rule_set = rule_set.unwrap_or(RuleSet::Classic);
+    rule_set_moves(deps.storage, &rule_set)?;
+
+    let timeout = timeout.unwrap_or(DEFAULT_GAME_TIMEOUT);
+    if timeout == Duration::Height(0) || timeout == Duration::Time(0) {
+        return Err(ContractError::InvalidTimeout {});
+    }
+
+    let id = SEASON_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    SEASON_COUNT.save(deps.storage, &id)?;
+
+    let season = Season {
+        id,
+        status: SeasonStatus::Registration,
+        max_participants,
+        payouts_bps,
+        rule_set,
+        timeout,
+        prize_pool: vec![],
+        matches: vec![],
+        standings: vec![],
+    };
+    SEASONS.save(deps.storage, id, &season)?;
+
+    Ok(Response::new()
+        .add_attribute("execute", "create_season")
+        .add_attribute("season_id", id.to_string())
+        .add_attribute("max_participants", max_participants.to_string()))
+}
+
+pub fn try_join_season(
+    deps: DepsMut,
+    info: MessageInfo,
+    season_id: u64,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+
+    let mut season = load_season(deps.storage, season_id)?;
+
+    if season.status != SeasonStatus::Registration {
+        return Err(ContractError::SeasonStarted {});
+    }
+
+    if season.standings.iter().any(|s| s.player == info.sender) {
+        return Err(ContractError::AlreadyRegistered {});
+    }
+
+    if season.standings.len() == season.max_participants as usize {
+        return Err(ContractError::SeasonFull {});
+    }
+
+    season.standings.push(Standing {
+        player: info.sender.clone(),
+        points: 0,
+        wins: 0,
+        ties: 0,
+        losses: 0,
+    });
+    SEASONS.save(deps.storage, season_id, &season)?;
+
+    Ok(Response::new()
+        .add_attribute("execute", "join_season")
+        .add_attribute("season_id", season_id.to_string())
+        .add_attribute("player", info.sender))
+}
+
+pub fn try_fund_season(
+    deps: DepsMut,
+    info: MessageInfo,
+    season_id: u64,
+) -> Result<Response, ContractError> {
+    let mut season = load_season(deps.storage, season_id)?;
+
+    if season.status == SeasonStatus::Finished {
+        return Err(ContractError::SeasonFinished {});
+    }
+
+    let funds = normalize_wager(info.funds);
+    if funds.is_empty() {
+        return Err(ContractError::MissingWagerAmount {});
+    }
+
+    season.prize_pool = add_wagers(season.prize_pool, funds.clone())?;
+    SEASONS.save(deps.storage, season_id, &season)?;
+
+    Ok(Response::new()
+        .add_attribute("execute", "fund_season")
+        .add_attribute("season_id", season_id.to_string())
+        .add_attribute("sponsor", info.sender)
+        .add_attribute("amount", wager_to_string(&Balance::from(funds))))
+}
+
+pub fn try_start_season(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    season_id: u64,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let mut season = load_season(deps.storage, season_id)?;
+
+    if season.status != SeasonStatus::Registration {
+        return Err(ContractError::SeasonStarted {});
+    }
+
+    if season.standings.len() < 2 {
+        return Err(ContractError::NotEnoughParticipants {});
+    }
+
+    //every player meets every other player once
+    let players: Vec<Addr> = season.standings.iter().map(|s| s.player.clone()).collect();
+    let mut started = vec![];
+    for (i, host) in players.iter().enumerate() {
+        for opponent in players.iter().skip(i + 1) {
+            let game = GameState {
+                season_id: Some(season_id),
+                ..new_match(
+                    deps.storage,
+                    &env,
+                    host,
+                    opponent,
+                    &season.rule_set,
+                    season.timeout,
+                )?
+            };
+            games().save(deps.storage, game.id, &game)?;
+            season.matches.push(game.id);
+            started.push(events::game_started(&game));
+        }
+    }
+
+    season.status = SeasonStatus::InProgress;
+    SEASONS.save(deps.storage, season_id, &season)?;
+
+    Ok(Response::new()
+        .add_attribute("execute", "start_season")
+        .add_attribute("season_id", season_id.to_string())
+        .add_attribute("matches", season.matches.len().to_string())
+        .add_events(started))
+}
+
+pub fn try_finalize_season(
+    deps: DepsMut,
+    info: MessageInfo,
+    season_id: u64,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let mut season = load_season(deps.storage, season_id)?;
+
+    match season.status {
+        SeasonStatus::InProgress if season.matches.is_empty() => {}
+        SeasonStatus::Finished => return Err(ContractError::SeasonFinished {}),
+        _ => return Err(ContractError::SeasonNotOver {}),
+    }
+
+    //players level on points and wins share a place
+    season
+        .standings
+        .sort_by_key(|s| std::cmp::Reverse((s.points, s.wins)));
+    let mut places: Vec<Vec<Addr>> = vec![];
+    for (i, standing) in season.standings.iter().enumerate() {
+        match i.checked_sub(1).map(|prev| &season.standings[prev]) {
+            Some(prev) if (prev.points, prev.wins) == (standing.points, standing.wins) => {
+                if let Some(place) = places.last_mut() {
+                    place.push(standing.player.clone());
+                }
+            }
+            _ => places.push(vec![standing.player.clone()]),
+        }
+    }
+
+    let msgs = place_payouts(&season.prize_pool, places, &season.payouts_bps)?;
+
+    season.status = SeasonStatus::Finished;
+    SEASONS.save(deps.storage, season_id, &season)?;
+
+    Ok(Response::new()
+        .add_attribute("execute", "finalize_season")
+        .add_attribute("season_id", season_id.to_string())
+        .add_attribute("winner", season.standings[0].player.to_string())
+        .add_messages(msgs))
+}
+
+pub fn try_play_house(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    player_move: GameMove,
+    rule_set: Option<RuleSet>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let randomness_provider = config
+        .randomness_provider
+        .ok_or(ContractError::HouseDisabled {})?;
+
+    let wager = normalize_wager(info.funds);
+    if wager.is_empty() {
+        return Err(ContractError::MissingWagerAmount {});
+    }
+
+    let rule_set = rule_set.unwrap_or(RuleSet::Classic);
+    let moves = rule_set_moves(deps.storage, &rule_set)?;
+    if !moves.iter().any(|m| m == player_move.as_str()) {
+        return Err(ContractError::InvalidMove {});
+    }
+
+    //the house has to be able to pay out every bet it takes
+    let mut bankroll = HOUSE_BANKROLL.may_load(deps.storage)?.unwrap_or_default();
+    for coin in wager.iter() {
+        if coin.amount > max_house_bet(&bankroll, &coin.denom, config.max_house_bet_bps) {
+            return Err(ContractError::BetTooLarge {
+                max_bps: config.max_house_bet_bps,
+            });
+        }
+    }
+    bankroll = sub_wagers(bankroll, &wager)?;
+    HOUSE_BANKROLL.save(deps.storage, &bankroll)?;
+
+    let id = GAME_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    GAME_COUNT.save(deps.storage, &id)?;
+
+    let game = HouseGame {
+        id,
+        player: info.sender.clone(),
+        player_move,
+        rule_set,
+        wager: wager.clone(),
+        expires: DEFAULT_GAME_TIMEOUT.after(&env.block),
+    };
+    HOUSE_GAMES.save(deps.storage, id, &game)?;
+
+    let request = WasmMsg::Execute {
+        contract_addr: randomness_provider.to_string(),
+        msg: to_binary(&RandomnessProviderMsg::GetNextRandomness {
+            job_id: house_job_id(id),
+        })?,
+        funds: vec![],
+    };
+
+    Ok(Response::new()
+        .add_attribute("execute", "play_house")
+        .add_attribute("game_id", id.to_string())
+        .add_attribute("player", info.sender)
+        .add_attribute("wager", wager_to_string(&Balance::from(wager)))
+        .add_event(events::house_game_started(&game))
+        .add_message(request))
+}
+
+pub fn try_receive_randomness(
+    deps: DepsMut,
+    info: MessageInfo,
+    job_id: String,
+    randomness: Binary,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.randomness_provider != Some(info.sender) {
+        return Err(ContractError::NotRandomnessProvider {});
+    }
+
+    if randomness.len() != 32 {
+        return Err(ContractError::InvalidRandomness {});
+    }
+
+    let game_id = job_id
+        .strip_prefix("house-")
+        .and_then(|id| id.parse::<u64>().ok())
+        .ok_or(ContractError::GameNotFound {})?;
+    let game = HOUSE_GAMES
+        .may_load(deps.storage, game_id)?
+        .ok_or(ContractError::GameNotFound {})?;
+
+    let moves = rule_set_moves(deps.storage, &game.rule_set)?;
+    let mut seed = [0u8; 16];
+    seed.copy_from_slice(&randomness[..16]);
+    let house_move = moves[(u128::from_be_bytes(seed) % moves.len() as u128) as usize].clone();
+    let result = get_game_result(
+        &moves,
+        &game.player_move,
+        &GameMove::Custom(house_move.clone()),
+    )?;
+
+    //the house's reserve goes back to the bankroll unless the player won it
+    let (payout, returned) = match result {
+        GameResult::HostWins => (add_wagers(game.wager.clone(), game.wager.clone())?, vec![]),
+        GameResult::Tie => (game.wager.clone(), game.wager.clone()),
+        GameResult::OpponentWins => (vec![], add_wagers(game.wager.clone(), game.wager.clone())?),
+    };
+
+    let bankroll = HOUSE_BANKROLL.may_load(deps.storage)?.unwrap_or_default();
+    HOUSE_BANKROLL.save(deps.storage, &add_wagers(bankroll, returned.clone())?)?;
+    HOUSE_GAMES.remove(deps.storage, game_id);
+
+    let settled = events::house_game_settled(
+        &game,
+        &house_move,
+        &result,
+        &Balance::from(payout.clone()),
+        &Balance::from(returned),
+    );
+
+    let game_result = match result {
+        GameResult::HostWins => "player_wins",
+        GameResult::OpponentWins => "house_wins",
+        GameResult::Tie => "tie",
+    };
+
+    Ok(Response::new()
+        .add_attribute("execute", "receive_randomness")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("player", game.player.to_string())
+        .add_attribute("house_move", house_move)
+        .add_attribute("game_result", game_result)
+        .add_event(settled)
+        .add_messages(transfer_msgs(&game.player, Balance::from(payout))?))
+}
+
+//the beacon never answered, so the player and the house both get their stakes back
+fn try_claim_house_timeout(
+    deps: DepsMut,
+    env: Env,
+    game: HouseGame,
+) -> Result<Response, ContractError> {
+    if !game.expires.is_expired(&env.block) {
+        return Err(ContractError::GameNotExpired {});
+    }
+
+    let bankroll = HOUSE_BANKROLL.may_load(deps.storage)?.unwrap_or_default();
+    HOUSE_BANKROLL.save(deps.storage, &add_wagers(bankroll, game.wager.clone())?)?;
+    HOUSE_GAMES.remove(deps.storage, game.id);
+
+    Ok(Response::new()
+        .add_attribute("execute", "claim_timeout")
+        .add_attribute("game_id", game.id.to_string())
+        .add_attribute("player", game.player.to_string())
+        .add_attribute("game_result", "refund")
+        .add_event(events::house_game_cancelled(&game))
+        .add_messages(transfer_msgs(&game.player, Balance::from(game.wager))?))
+}
+
+pub fn try_fund_house(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let funds = normalize_wager(info.funds);
+    if funds.is_empty() {
+        return Err(ContractError::MissingWagerAmount {});
+    }
+
+    let bankroll = HOUSE_BANKROLL.may_load(deps.storage)?.unwrap_or_default();
+    let bankroll = add_wagers(bankroll, funds.clone())?;
+    HOUSE_BANKROLL.save(deps.storage, &bankroll)?;
+
+    Ok(Response::new()
+        .add_attribute("execute", "fund_house")
+        .add_attribute("amount", wager_to_string(&Balance::from(funds)))
+        .add_attribute("bankroll", wager_to_string(&Balance::from(bankroll))))
+}
+
+pub fn try_withdraw_house(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Vec<Coin>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let amount = normalize_wager(amount);
+    let bankroll = HOUSE_BANKROLL.may_load(deps.storage)?.unwrap_or_default();
+    let bankroll = sub_wagers(bankroll, &amount)?;
+    HOUSE_BANKROLL.save(deps.storage, &bankroll)?;
+
+    Ok(Response::new()
+        .add_attribute("execute", "withdraw_house")
+        .add_attribute("amount", wager_to_string(&Balance::from(amount.clone())))
+        .add_attribute("bankroll", wager_to_string(&Balance::from(bankroll)))
+        .add_messages(transfer_msgs(&info.sender, Balance::from(amount))?))
+}
+
+fn house_job_id(game_id: u64) -> String {
+    format!("house-{}", game_id)
+}
+
+fn max_house_bet(bankroll: &[Coin], denom: &str, max_house_bet_bps: u16) -> Uint128 {
+    bankroll
+        .iter()
+        .find(|coin| coin.denom == denom)
+        .map(|coin| coin.amount.multiply_ratio(max_house_bet_bps, 10_000u16))
+        .unwrap_or_default()
+}
+
+/// Takes `b` out of `a`, every coin of `b` has to be covered
+fn sub_wagers(a: Vec<Coin>, b: &[Coin]) -> Result<Vec<Coin>, ContractError> {
+    let mut balance = NativeBalance(a);
+    for coin in b {
+        balance = (balance - coin.clone()).map_err(|_| ContractError::InsufficientBankroll {})?;
+    }
+    balance.normalize();
+    Ok(balance.into_vec())
+}
+
+pub fn try_register_rule_set(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+    moves: Vec<String>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    //rule sets are listed by name, so the built-in names are taken too
+    let built_in = [RuleSet::Classic, RuleSet::Rpsls]
+        .iter()
+        .any(|rule_set| rule_set_to_string(rule_set) == name);
+    if built_in || RULE_SETS.has(deps.storage, &name) {
+        return Err(ContractError::RuleSetAlreadyExists {});
+    }
+
+    //an odd number of moves lets every move beat exactly half of the others
+    let mut unique = moves.clone();
+    unique.sort();
+    unique.dedup();
+    if moves.len() < 3
+        || moves.len() > MAX_RULE_SET_MOVES
+        || moves.len() % 2 == 0
+        || unique.len() != moves.len()
+        || moves.iter().any(String::is_empty)
+    {
+        return Err(ContractError::InvalidRuleSet {
+            max: MAX_RULE_SET_MOVES,
+        });
+    }
+
+    RULE_SETS.save(deps.storage, &name, &moves)?;
+
+    Ok(Response::new()
+        .add_attribute("execute", "register_rule_set")
+        .add_attribute("name", name)
+        .add_attribute("moves", moves.join(",")))
+}
+
+pub fn try_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    fee_bps: Option<u16>,
+    fee_collector: Option<String>,
+    randomness_provider: Option<String>,
+    max_house_bet_bps: Option<u16>,
+    allowed_denoms: Option<Vec<DenomLimit>>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    //only the fields that were changed end up in the attributes
+    let mut attributes = vec![];
+
+    if let Some(fee_bps) = fee_bps {
+        if fee_bps > MAX_FEE_BPS {
+            return Err(ContractError::InvalidFee { max: MAX_FEE_BPS });
+        }
+        config.fee_bps = fee_bps;
+        attributes.push(attr("fee_bps", fee_bps.to_string()));
+    }
+
+    if let Some(fee_collector) = fee_collector {
+        config.fee_collector = deps.api.addr_validate(&fee_collector)?;
+        attributes.push(attr("fee_collector", &config.fee_collector));
+    }
+
+    if let Some(randomness_provider) = randomness_provider {
+        let randomness_provider = deps.api.addr_validate(&randomness_provider)?;
+        attributes.push(attr("randomness_provider", &randomness_provider));
+        config.randomness_provider = Some(randomness_provider);
+    }
+
+    if let Some(max_house_bet_bps) = max_house_bet_bps {
+        if max_house_bet_bps > MAX_HOUSE_BET_BPS {
+            return Err(ContractError::BetTooLarge {
+                max_bps: MAX_HOUSE_BET_BPS,
+            });
+        }
+        config.max_house_bet_bps = max_house_bet_bps;
+        attributes.push(attr("max_house_bet_bps", max_house_bet_bps.to_string()));
+    }
+
+    if let Some(mut allowed_denoms) = allowed_denoms {
+        allowed_denoms.sort_by(|a, b| a.denom.cmp(&b.denom));
+        let invalid = allowed_denoms
+            .iter()
+            .any(|limit| limit.denom.is_empty() || limit.max.map_or(false, |max| max < limit.min))
+            || allowed_denoms.windows(2).any(|w| w[0].denom == w[1].denom);
+        if invalid {
+            return Err(ContractError::InvalidDenomLimits {});
+        }
+
+        attributes.push(attr(
+            "allowed_denoms",
+            allowed_denoms
+                .iter()
+                .map(denom_limit_to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+        ));
+        config.allowed_denoms = allowed_denoms;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("execute", "update_config")
+        .add_attribute("admin", info.sender)
+        .add_attributes(attributes))
+}
+
+pub fn try_clear_randomness_provider(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.randomness_provider = None;
+        Ok(config)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("execute", "clear_randomness_provider")
+        .add_attribute("admin", info.sender))
+}
+
+pub fn try_update_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    admin: Option<String>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let new_admin = admin
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    //only the admin can unpause or withdraw the bankroll, so neither may be left behind
+    if new_admin.is_none() {
+        if CONFIG.load(deps.storage)?.paused {
+            return Err(ContractError::RenounceWhilePaused {});
+        }
+        let bankroll = HOUSE_BANKROLL.may_load(deps.storage)?.unwrap_or_default();
+        let house_games = HOUSE_GAMES
+            .keys(deps.storage, None, None, Order::Ascending)
+            .next();
+        if bankroll.iter().any(|coin| !coin.amount.is_zero()) || house_games.is_some() {
+            return Err(ContractError::RenounceWithBankroll {});
+        }
+    }
+
+    ADMIN.set(deps, new_admin.clone())?;
+
+    Ok(Response::new()
+        .add_attribute("execute", "update_admin")
+        .add_attribute("admin", info.sender)
+        .add_attribute(
+            "new_admin",
+            new_admin.map_or_else(|| "None".to_string(), String::from),
+        ))
+}
+
+pub fn try_set_paused(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        match (paused, config.paused_since) {
+            (true, None) => config.paused_since = Some(ChainTime::now(&env.block)),
+            (false, Some(_)) => {
+                config.paused_total = paused_total(&config, &env.block);
+                config.paused_since = None;
+            }
+            _ => {}
+        }
+        config.paused = paused;
+        Ok(config)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("execute", if paused { "pause" } else { "unpause" })
+        .add_attribute("admin", info.sender))
+}
+
+fn assert_wager_allowed(config: &Config, wager: &Balance) -> Result<(), ContractError> {
+    if config.allowed_denoms.is_empty() {
+        return Ok(());
+    }
+
+    for (denom, amount) in wager_denoms(wager) {
+        let limit = config
+            .allowed_denoms
+            .iter()
+            .find(|limit| limit.denom == denom)
+            .ok_or_else(|| ContractError::DenomNotAllowed {
+                denom: denom.clone(),
+            })?;
+        if amount < limit.min {
+            return Err(ContractError::WagerTooSmall {
+                denom,
+                min: limit.min,
+            });
+        }
+        if let Some(max) = limit.max {
+            if amount > max {
+                return Err(ContractError::WagerTooLarge { denom, max });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn denom_limit_to_string(limit: &DenomLimit) -> String {
+    match limit.max {
+        Some(max) => format!("{}:{}-{}", limit.denom, limit.min, max),
+        None => format!("{}:{}-", limit.denom, limit.min),
+    }
+}
+
+fn assert_not_paused(storage: &dyn Storage) -> Result<(), ContractError> {
+    if CONFIG.load(storage)?.paused {
+        return Err(ContractError::Paused {});
+    }
+    Ok(())
+}
+
+//time spent paused so far, including a pause that's still going on
+fn paused_total(config: &Config, block: &BlockInfo) -> ChainTime {
+    let mut total = config.paused_total;
+    if let Some(since) = config.paused_since {
+        total.height += block.height.saturating_sub(since.height);
+        total.seconds += block.time.seconds().saturating_sub(since.seconds);
+    }
+    total
+}
+
+/// When `game` runs out of time. Its clock stands still while the contract is paused,
+/// so `expires` is pushed back by the time spent paused since it was set.
+pub fn game_expires(config: &Config, block: &BlockInfo, game: &GameState) -> Expiration {
+    let total = paused_total(config, block);
+    match game.expires {
+        Expiration::AtHeight(height) => {
+            Expiration::AtHeight(height + total.height - game.paused_offset.height)
+        }
+        Expiration::AtTime(time) => {
+            Expiration::AtTime(time.plus_seconds(total.seconds - game.paused_offset.seconds))
+        }
+        Expiration::Never {} => Expiration::Never {},
+    }
+}
+
+//gives the player whose turn it is a full timeout
+fn restart_clock(game: &mut GameState, config: &Config, block: &BlockInfo) {
+    game.expires = game.timeout.after(block);
+    game.paused_offset = paused_total(config, block);
+}
+
+//commitment must look like a sha256 digest so a typo can't lock the wager
+fn validate_commitment(commitment: String) -> Result<String, ContractError> {
+    let commitment = commitment.to_lowercase();
+    if commitment.len() != 64 || !commitment.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ContractError::InvalidCommitment {});
+    }
+    Ok(commitment)
+}
+
+/// Hex encoded sha256 of the move name's length in bytes, `:`, the move name and the
+/// salt, e.g. `sha256("4:rock" + salt)`. Hosts commit to this value in `StartGame` and
+/// later reveal the move and salt that produce it. The length prefix stops a commitment
+/// from opening to two moves when one move name is a prefix of another.
+pub fn hash_move(game_move: &GameMove, salt: &str) -> String {
+    let game_move = game_move.as_str();
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}:{}", game_move.len(), game_move).as_bytes());
+    hasher.update(salt.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+fn load_game(storage: &dyn Storage, game_id: u64) -> Result<GameState, ContractError> {
+    games()
+        .may_load(storage, game_id)?
+        .ok_or(ContractError::GameNotFound {})
+}
+
+fn remove_open_challenge(storage: &mut dyn Storage, game: &GameState) {
+    if game.opponent.is_none() {
+        for (denom, amount) in wager_denoms(&game.host_wager) {
+            OPEN_CHALLENGES.remove(storage, (&denom, amount.u128(), game.id));
+        }
+    }
+}
+
+/// Pays out the pot of a finished game, records it in both players' stats and moves
+/// its tournament on to the next match
+fn settle_game(
+    storage: &mut dyn Storage,
+    env: &Env,
+    game: &GameState,
+    opponent: &Addr,
+    result: &GameResult,
+    config: &Config,
+) -> StdResult<(Vec<CosmosMsg>, Balance, Vec<Event>)> {
+    let host_wager = game.host_wager.clone();
+    let opp_wager = game.opp_wager.clone().unwrap_or_default();
+
+    let (payout, _) = split_fee(
+        add_balances(host_wager.clone(), opp_wager.clone())?,
+        config.fee_bps,
+    )?;
+    let (host_won, opp_won) = match result {
+        GameResult::Tie => (host_wager.clone(), opp_wager.clone()),
+        GameResult::HostWins => (payout, Balance::default()),
+        GameResult::OpponentWins => (Balance::default(), payout),
+    };
+
+    update_player_stats(storage, &game.host, result, true, &host_wager, &host_won)?;
+    update_player_stats(storage, opponent, result, false, &opp_wager, &opp_won)?;
+    update_ratings(storage, &game.host, opponent, result)?;
+
+    let (mut msgs, fee) = send_funds_to_winner(
+        result.clone(),
+        opp_wager,
+        host_wager,
+        game.host.clone(),
+        opponent.clone(),
+        config,
+    )?;
+
+    let mut events = vec![events::game_settled(
+        game,
+        opponent,
+        Settlement {
+            result,
+            host_payout: &host_won,
+            opp_payout: &opp_won,
+            fee: &fee,
+            fee_collector: &config.fee_collector,
+        },
+    )];
+
+    if let Some(season_id) = game.season_id {
+        record_season_result(storage, season_id, game, opponent, result)?;
+    }
+
+    if let Some(tournament_id) = game.tournament_id {
+        let (winner, loser) = match result {
+            GameResult::OpponentWins => (opponent.clone(), game.host.clone()),
+            _ => (game.host.clone(), opponent.clone()),
+        };
+        let (next_msgs, next_events) =
+            advance_tournament(storage, env, tournament_id, game.id, winner, loser)?;
+        msgs.extend(next_msgs);
+        events.extend(next_events);
+    }
+
+    Ok((msgs, fee, events))
+}
+
+fn archive_game(storage: &mut dyn Storage, env: &Env, game: &GameState) -> StdResult<()> {
+    GAME_HISTORY.save(
+        storage,
+        game.id,
+        &SettledGame {
+            game: game.clone(),
+            height: env.block.height,
+            time: env.block.time,
+        },
+    )?;
+    PLAYER_HISTORY.save(storage, (&game.host, game.id), &Empty {})?;
+    if let Some(opponent) = &game.opponent {
+        PLAYER_HISTORY.save(storage, (opponent, game.id), &Empty {})?;
+    }
+    Ok(())
+}
+
+fn load_tournament(storage: &dyn Storage, tournament_id: u64) -> Result<Tournament, ContractError> {
+    TOURNAMENTS
+        .may_load(storage, tournament_id)?
+        .ok_or(ContractError::TournamentNotFound {})
+}
+
+/// Pairs `players` off in order into the next round's matches, an odd player out gets a bye.
+/// The last player standing wins and the prize pool is paid out.
+fn start_tournament_round(
+    storage: &mut dyn Storage,
+    env: &Env,
+    tournament: &mut Tournament,
+    players: Vec<Addr>,
+) -> StdResult<(Vec<CosmosMsg>, Vec<Event>)> {
+    if players.len() == 1 {
+        tournament.status = TournamentStatus::Finished;
+        tournament.champion = Some(players[0].clone());
+        return Ok((tournament_payouts(tournament, &players[0])?, vec![]));
+    }
+
+    tournament.status = TournamentStatus::InProgress;
+    tournament.round += 1;
+    tournament.eliminated.push(vec![]);
+
+    let mut started = vec![];
+    for pair in players.chunks(2) {
+        let (host, opponent) = match pair {
+            [host, opponent] => (host, opponent),
+            _ => {
+                tournament.advanced.push(pair[0].clone());
+                continue;
+            }
+        };
+
+        let game = GameState {
+            tournament_id: Some(tournament.id),
+            ..new_match(
+                storage,
+                env,
+                host,
+                opponent,
+                &tournament.rule_set,
+                tournament.timeout,
+            )?
+        };
+        games().save(storage, game.id, &game)?;
+        tournament.matches.push(game.id);
+        started.push(events::game_started(&game));
+    }
+
+    Ok((vec![], started))
+}
+
+/// Bracket and league matches are played like a series with nothing at stake, so the
+/// host starts by committing to a move
+fn new_match(
+    storage: &mut dyn Storage,
+    env: &Env,
+    host: &Addr,
+    opponent: &Addr,
+    rule_set: &RuleSet,
+    timeout: Duration,
+) -> StdResult<GameState> {
+    let id = GAME_COUNT.may_load(storage)?.unwrap_or_default() + 1;
+    GAME_COUNT.save(storage, &id)?;
+
+    Ok(GameState {
+        id,
+        status: GameStatus::AwaitingCommitment,
+        host: host.clone(),
+        opponent: Some(opponent.clone()),
+        host_wager: Balance::default(),
+        opp_wager: Some(Balance::default()),
+        host_move_commitment: String::new(),
+        host_move: None,
+        opp_move: None,
+        result: None,
+        rule_set: rule_set.clone(),
+        tournament_id: None,
+        season_id: None,
+        best_of: 1,
+        rounds: vec![],
+        host_score: 0,
+        opp_score: 0,
+        timeout,
+        expires: timeout.after(&env.block),
+        paused_offset: paused_total(&CONFIG.load(storage)?, &env.block),
+    })
+}
+
+fn advance_tournament(
+    storage: &mut dyn Storage,
+    env: &Env,
+    tournament_id: u64,
+    game_id: u64,
+    winner: Addr,
+    loser: Addr,
+) -> StdResult<(Vec<CosmosMsg>, Vec<Event>)> {
+    let mut tournament = TOURNAMENTS.load(storage, tournament_id)?;
+
+    tournament.matches.retain(|id| *id != game_id);
+    tournament.advanced.push(winner);
+    if let Some(eliminated) = tournament.eliminated.last_mut() {
+        eliminated.push(loser);
+    }
+
+    let mut res = (vec![], vec![]);
+    if tournament.matches.is_empty() {
+        let players = std::mem::take(&mut tournament.advanced);
+        res = start_tournament_round(storage, env, &mut tournament, players)?;
+    }
+
+    TOURNAMENTS.save(storage, tournament_id, &tournament)?;
+
+    Ok(res)
+}
+
+/// Pays out the entry fees, players knocked out in the same round share a place
+fn tournament_payouts(tournament: &Tournament, champion: &Addr) -> StdResult<Vec<CosmosMsg>> {
+    let pool = Coin {
+        denom: tournament.entry_fee.denom.clone(),
+        amount: tournament
+            .entry_fee
+            .amount
+            .checked_mul(Uint128::from(tournament.participants.len() as u128))?,
+    };
+
+    let mut standings = vec![vec![champion.clone()]];
+    standings.extend(tournament.eliminated.iter().rev().cloned());
+
+    place_payouts(&[pool], standings, &tournament.payouts_bps)
+}
+
+/// Splits a prize pool by finishing place. `standings` groups players sharing a place, they
+/// split the shares of the places they take up equally. Rounding dust and the shares of
+/// places nobody finished in go to the winner.
+fn place_payouts(
+    pool: &[Coin],
+    standings: Vec<Vec<Addr>>,
+    payouts_bps: &[u16],
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut payouts: Vec<(Addr, Vec<Coin>)> = vec![];
+    let mut place = 0;
+    for players in standings {
+        let share: u64 = payouts_bps
+            .iter()
+            .skip(place)
+            .take(players.len())
+            .map(|bps| *bps as u64)
+            .sum();
+        let amount: Vec<Coin> = pool
+            .iter()
+            .map(|coin| Coin {
+                denom: coin.denom.clone(),
+                amount: coin
+                    .amount
+                    .multiply_ratio(share, 10_000 * players.len() as u64),
+            })
+            .collect();
+
+        place += players.len();
+        payouts.extend(players.into_iter().map(|player| (player, amount.clone())));
+    }
+
+    for (i, coin) in pool.iter().enumerate() {
+        let paid = payouts
+            .iter()
+            .try_fold(Uint128::zero(), |total, (_, amount)| {
+                total.checked_add(amount[i].amount)
+            })?;
+        payouts[0].1[i].amount += coin.amount.checked_sub(paid)?;
+    }
+
+    Ok(payouts
+        .into_iter()
+        .filter_map(|(player, amount)| {
+            let amount: Vec<Coin> = amount
+                .into_iter()
+                .filter(|coin| !coin.amount.is_zero())
+                .collect();
+            if amount.is_empty() {
+                return None;
+            }
+            Some(
+                BankMsg::Send {
+                    to_address: player.to_string(),
+                    amount,
+                }
+                .into(),
+            )
+        })
+        .collect())
+}
+
+fn load_season(storage: &dyn Storage, season_id: u64) -> Result<Season, ContractError> {
+    SEASONS
+        .may_load(storage, season_id)?
+        .ok_or(ContractError::SeasonNotFound {})
+}
+
+fn record_season_result(
+    storage: &mut dyn Storage,
+    season_id: u64,
+    game: &GameState,
+    opponent: &Addr,
+    result: &GameResult,
+) -> StdResult<()> {
+    let mut season = SEASONS.load(storage, season_id)?;
+
+    season.matches.retain(|id| *id != game.id);
+    for standing in season.standings.iter_mut() {
+        let is_host = standing.player == game.host;
+        if !is_host && &standing.player != opponent {
+            continue;
+        }
+
+        match (result, is_host) {
+            (GameResult::Tie, _) => {
+                standing.ties += 1;
+                standing.points += POINTS_PER_TIE;
+            }
+            (GameResult::HostWins, true) | (GameResult::OpponentWins, false) => {
+                standing.wins += 1;
+                standing.points += POINTS_PER_WIN;
+            }
+            _ => standing.losses += 1,
+        }
+    }
+
+    SEASONS.save(storage, season_id, &season)
+}
+
+fn update_player_stats(
+    storage: &mut dyn Storage,
+    player: &Addr,
+    result: &GameResult,
+    is_host: bool,
+    wagered: &Balance,
+    won: &Balance,
+) -> StdResult<()> {
+    let mut stats = player_stats()
+        .may_load(storage, player.clone())?
+        .unwrap_or_else(|| PlayerStats::new(player.clone()));
+
+    match (result, is_host) {
+        (GameResult::Tie, _) => stats.ties += 1,
+        (GameResult::HostWins, true) | (GameResult::OpponentWins, false) => stats.wins += 1,
+        _ => stats.losses += 1,
+    }
+
+    player_stats().save(storage, player.clone(), &stats)?;
+
+    for (denom, amount) in wager_denoms(wagered) {
+        let mut denom_stats = load_player_denom_stats(storage, player, denom)?;
+        denom_stats.total_wagered = denom_stats.total_wagered.checked_add(amount)?;
+        save_player_denom_stats(storage, &denom_stats)?;
+    }
+
+    for (denom, amount) in wager_denoms(won) {
+        let mut denom_stats = load_player_denom_stats(storage, player, denom)?;
+        denom_stats.total_won = denom_stats.total_won.checked_add(amount)?;
+        save_player_denom_stats(storage, &denom_stats)?;
+    }
+
+    Ok(())
+}
+
+/// Expected score per mille of the higher rated player, for rating gaps in steps of 25 up to 800
+const EXPECTED_SCORES: [i64; 33] = [
+    500, 536, 571, 606, 640, 673, 703, 733, 760, 785, 808, 830, 849, 867, 882, 896, 909, 920, 930,
+    939, 947, 954, 960, 965, 969, 973, 977, 980, 983, 985, 987, 989, 990,
+];
+
+/// Expected score per mille of a player rated `rating` against one rated `opp_rating`
+pub fn expected_score(rating: u32, opp_rating: u32) -> i64 {
+    let diff = (rating as i64 - opp_rating as i64).clamp(-800, 800);
+    let gap = diff.abs();
+
+    //interpolate between the two closest steps of the table
+    let step = (gap / 25) as usize;
+    let lower = EXPECTED_SCORES[step];
+    let upper = EXPECTED_SCORES[(step + 1).min(EXPECTED_SCORES.len() - 1)];
+    let score = lower + (upper - lower) * (gap % 25) / 25;
+
+    if diff >= 0 {
+        score
+    } else {
+        1000 - score
+    }
+}
+
+fn update_ratings(
+    storage: &mut dyn Storage,
+    host: &Addr,
+    opponent: &Addr,
+    result: &GameResult,
+) -> StdResult<()> {
+    let mut host_rating = load_rating(storage, host)?;
+    let mut opp_rating = load_rating(storage, opponent)?;
+
+    let host_score = match result {
+        GameResult::HostWins => 1000,
+        GameResult::OpponentWins => 0,
+        GameResult::Tie => 500,
+    };
+
+    //whatever the host gains the opponent loses, rounded to the nearest point
+    let delta =
+        RATING_K_FACTOR * (host_score - expected_score(host_rating.rating, opp_rating.rating));
+    let delta = (delta + delta.signum() * 500) / 1000;
+
+    host_rating.rating = (host_rating.rating as i64 + delta).max(0) as u32;
+    host_rating.games_played += 1;
+    opp_rating.rating = (opp_rating.rating as i64 - delta).max(0) as u32;
+    opp_rating.games_played += 1;
+
+    ratings().save(storage, host.clone(), &host_rating)?;
+    ratings().save(storage, opponent.clone(), &opp_rating)
+}
+
+fn load_rating(storage: &dyn Storage, player: &Addr) -> StdResult<PlayerRating> {
+    Ok(ratings()
+        .may_load(storage, player.clone())?
+        .unwrap_or(PlayerRating {
+            address: player.clone(),
+            rating: INITIAL_RATING,
+            games_played: 0,
+        }))
+}
+
+fn load_player_denom_stats(
+    storage: &dyn Storage,
+    player: &Addr,
+    denom: String,
+) -> StdResult<PlayerDenomStats> {
+    Ok(player_denom_stats()
+        .may_load(storage, (player.clone(), denom.clone()))?
+        .unwrap_or(PlayerDenomStats {
+            address: player.clone(),
+            denom,
+            total_wagered: Uint128::zero(),
+            total_won: Uint128::zero(),
+        }))
+}
+
+fn save_player_denom_stats(storage: &mut dyn Storage, stats: &PlayerDenomStats) -> StdResult<()> {
+    player_denom_stats().save(storage, (stats.address.clone(), stats.denom.clone()), stats)
+}
+
+/// Moves of a rule set in order, each move beats the next half of the list
+pub fn rule_set_moves(
+    storage: &dyn Storage,
+    rule_set: &RuleSet,
+) -> Result<Vec<String>, ContractError> {
+    match rule_set {
+        RuleSet::Classic => Ok(CLASSIC_MOVES.iter().map(|m| m.to_string()).collect()),
+        RuleSet::Rpsls => Ok(RPSLS_MOVES.iter().map(|m| m.to_string()).collect()),
+        RuleSet::Custom { name } => RULE_SETS
+            .may_load(storage, name)?
+            .ok_or(ContractError::RuleSetNotFound {}),
+    }
+}
+
+pub fn get_game_result(
+    moves: &[String],
+    host_move: &GameMove,
+    opp_move: &GameMove,
+) -> Result<GameResult, ContractError> {
+    let position = |game_move: &GameMove| {
+        moves
+            .iter()
+            .position(|m| m == game_move.as_str())
+            .ok_or(ContractError::InvalidMove {})
+    };
+    let host = position(host_move)?;
+    let opp = position(opp_move)?;
+
+    //how far the opponent's move is after the host's, wrapping around
+    let distance = (opp + moves.len() - host) % moves.len();
+    if distance == 0 {
+        Ok(GameResult::Tie)
+    } else if distance <= moves.len() / 2 {
+        Ok(GameResult::HostWins)
+    } else {
+        Ok(GameResult::OpponentWins)
+    }
+}
+
+/// Returns the payout messages and the house fee taken, ties are refunded without a fee
+pub fn send_funds_to_winner(
+    result: GameResult,
+    opp_wager: Balance,
+    host_wager: Balance,
+    host: Addr,
+    opponent: Addr,
+    config: &Config,
+) -> StdResult<(Vec<CosmosMsg>, Balance)> {
+    let mut msgs: Vec<CosmosMsg> = vec![];
+
+    let winner = match result {
+        GameResult::Tie => {
+            msgs.extend(transfer_msgs(&host, host_wager)?);
+            msgs.extend(transfer_msgs(&opponent, opp_wager)?);
+
+            return Ok((msgs, Balance::default()));
+        }
+        GameResult::HostWins => host,
+        GameResult::OpponentWins => opponent,
+    };
+
+    let (payout, fee) = split_fee(add_balances(host_wager, opp_wager)?, config.fee_bps)?;
+
+    msgs.extend(transfer_msgs(&winner, payout)?);
+    msgs.extend(transfer_msgs(&config.fee_collector, fee.clone())?);
+
+    Ok((msgs, fee))
+}
+
+/// Splits a pot into the winner's payout and the house fee, rounding the fee down
+pub fn split_fee(pot: Balance, fee_bps: u16) -> StdResult<(Balance, Balance)> {
+    let fee_of = |amount: Uint128| amount.multiply_ratio(fee_bps, 10_000u128);
+
+    match pot {
+        Balance::Native(native) => {
+            let mut payout = vec![];
+            let mut fee = vec![];
+
+            for coin in native.into_vec() {
+                let fee_amount = fee_of(coin.amount);
+                payout.push(Coin {
+                    denom: coin.denom.clone(),
+                    amount: coin.amount.checked_sub(fee_amount)?,
+                });
+                fee.push(Coin {
+                    denom: coin.denom,
+                    amount: fee_amount,
+                });
+            }
+
+            Ok((
+                Balance::from(normalize_wager(payout)),
+                Balance::from(normalize_wager(fee)),
+            ))
+        }
+        Balance::Cw20(token) => {
+            let fee_amount = fee_of(token.amount);
+
+            Ok((
+                Balance::Cw20(Cw20CoinVerified {
+                    address: token.address.clone(),
+                    amount: token.amount.checked_sub(fee_amount)?,
+                }),
+                Balance::Cw20(Cw20CoinVerified {
+                    address: token.address,
+                    amount: fee_amount,
+                }),
+            ))
+        }
+    }
+}
+
+fn fee_attributes(fee: &Balance, config: &Config) -> Vec<Attribute> {
+    if fee.is_empty() {
+        return vec![];
+    }
+
+    vec![
+        attr("fee", wager_to_string(fee)),
+        attr("fee_collector", config.fee_collector.to_string()),
+    ]
+}
+
+/// `BankMsg::Send` for native coins or a cw20 `Transfer` for tokens, nothing
+/// for an empty balance.
+pub fn transfer_msgs(recipient: &Addr, balance: Balance) -> StdResult<Vec<CosmosMsg>> {
+    if balance.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let msg = match balance {
+        Balance::Native(native) => BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: native.into_vec(),
+        }
+        .into(),
+        Balance::Cw20(token) => WasmMsg::Execute {
+            contract_addr: token.address.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: token.amount,
+            })?,
+            funds: vec![],
+        }
+        .into(),
+    };
+
+    Ok(vec![msg])
+}
+
+/// Sorts coins by denom, merging duplicate denoms and dropping zero amounts, so
+/// wagers can be compared regardless of the order the coins were sent in.
+pub fn normalize_wager(wager: Vec<Coin>) -> Vec<Coin> {
+    let mut balance = NativeBalance(wager);
+    balance.normalize();
+    balance.into_vec()
+}
+
+pub fn normalize_balance(balance: Balance) -> Balance {
+    match balance {
+        Balance::Native(native) => Balance::from(normalize_wager(native.into_vec())),
+        Balance::Cw20(token) => Balance::Cw20(token),
+    }
+}
+
+/// Sums two wagers denom by denom, the result is normalized.
+pub fn add_wagers(a: Vec<Coin>, b: Vec<Coin>) -> StdResult<Vec<Coin>> {
+    let mut total = normalize_wager(a);
+
+    for coin in normalize_wager(b) {
+        match total.iter_mut().find(|c| c.denom == coin.denom) {
+            Some(existing) => existing.amount = existing.amount.checked_add(coin.amount)?,
+            None => total.push(coin),
+        }
+    }
+
+    Ok(normalize_wager(total))
+}
+
+pub fn add_balances(a: Balance, b: Balance) -> StdResult<Balance> {
+    match (a, b) {
+        (Balance::Native(a), Balance::Native(b)) => {
+            Ok(Balance::from(add_wagers(a.into_vec(), b.into_vec())?))
+        }
+        (Balance::Cw20(a), Balance::Cw20(b)) if a.address == b.address => {
+            Ok(Balance::Cw20(Cw20CoinVerified {
+                address: a.address,
+                amount: a.amount.checked_add(b.amount)?,
+            }))
+        }
+        _ => Err(StdError::generic_err(
+            "Cannot add wagers of different tokens",
+        )),
+    }
+}
+
+/// `(denom, amount)` pairs of a wager, cw20 tokens use their contract address as denom
+fn wager_denoms(wager: &Balance) -> Vec<(String, Uint128)> {
+    match wager {
+        Balance::Native(native) => native
+            .0
+            .iter()
+            .map(|coin| (coin.denom.clone(), coin.amount))
+            .collect(),
+        Balance::Cw20(token) => vec![(token.address.to_string(), token.amount)],
+    }
+}
+
+fn wager_to_string(wager: &Balance) -> String {
+    let mut res = String::new();
+
+    for (denom, amount) in wager_denoms(wager) {
+        res = res + &amount.to_string() + &denom + " ";
+    }
+
+    res
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Admin {} => to_binary(&ADMIN.query_admin(deps)?),
+        QueryMsg::GetConfig {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::GetAllowedDenoms {} => to_binary(&AllowedDenomsResponse {
+            denoms: CONFIG.load(deps.storage)?.allowed_denoms,
+        }),
+        QueryMsg::GetHouse {} => to_binary(&try_query_house(deps)?),
+        QueryMsg::GetHouseGame { game_id } => to_binary(&HOUSE_GAMES.load(deps.storage, game_id)?),
+        QueryMsg::GetRuleSets {
+            start_after,
+            limit,
+            order,
+        } => to_binary(&try_query_rule_sets(deps, start_after, limit, order)?),
+        QueryMsg::GetGame { game_id } => to_binary(&try_query_game(deps, env, game_id)?),
+        QueryMsg::GetGameByHostAndOpponent {
+            host,
+            opponent,
+            start_after,
+            limit,
+            order,
+        } => to_binary(&try_query_game_by_host_and_opponent(
+            deps,
+            env,
+            host,
+            opponent,
+            start_after,
+            limit,
+            order,
+        )?),
+        QueryMsg::GetGamesByHost {
+            host,
+            start_after,
+            limit,
+            order,
+        } => to_binary(&try_query_games_by_host(
+            deps,
+            env,
+            host,
+            start_after,
+            limit,
+            order,
+        )?),
+        QueryMsg::GetGamesByOpponent {
+            opponent,
+            start_after,
+            limit,
+            order,
+        } => to_binary(&try_query_games_by_opponent(
+            deps,
+            env,
+            opponent,
+            start_after,
+            limit,
+            order,
+        )?),
+        QueryMsg::GetPendingChallenges {
+            player,
+            start_after,
+            limit,
+            order,
+        } => to_binary(&try_query_pending_challenges(
+            deps,
+            env,
+            player,
+            start_after,
+            limit,
+            order,
+        )?),
+        QueryMsg::GetTournament { tournament_id } => {
+            to_binary(&TOURNAMENTS.load(deps.storage, tournament_id)?)
+        }
+        QueryMsg::GetSeason { season_id } => to_binary(&SEASONS.load(deps.storage, season_id)?),
+        QueryMsg::GetSeries { game_id } => to_binary(&try_query_series(deps, game_id)?),
+        QueryMsg::GetPlayerStats { address } => to_binary(&try_query_player_stats(deps, address)?),
+        QueryMsg::GetTopPlayers {
+            order_by,
+            start_after,
+            limit,
+            order,
+        } => to_binary(&try_query_top_players(
+            deps,
+            order_by,
+            start_after,
+            limit,
+            order,
+        )?),
+        QueryMsg::GetRating { address } => to_binary(&try_query_rating(deps, address)?),
+        QueryMsg::GetRatings {
+            start_after,
+            limit,
+            order,
+        } => to_binary(&try_query_ratings(deps, start_after, limit, order)?),
+        QueryMsg::GetHistoryByPlayer {
+            player,
+            start_after,
+            limit,
+            order,
+        } => to_binary(&try_query_history_by_player(
+            deps,
+            player,
+            start_after,
+            limit,
+            order,
+        )?),
+        QueryMsg::GetRecentGames {
+            start_after,
+            limit,
+            order,
+        } => to_binary(&try_query_recent_games(deps, start_after, limit, order)?),
+        QueryMsg::GetOpenChallenges {
+            denom,
+            min_amount,
+            max_amount,
+            start_after,
+            limit,
+            order,
+        } => to_binary(&try_query_open_challenges(
+            deps,
+            env,
+            denom,
+            min_amount,
+            max_amount,
+            start_after,
+            limit,
+            order,
+        )?),
+    }
+}
+
+pub fn try_query_game(deps: Deps, env: Env, game_id: u64) -> StdResult<GameState> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut game = games().load(deps.storage, game_id)?;
+    game.expires = game_expires(&config, &env.block, &game);
+    Ok(game)
+}
+
+pub fn try_query_game_by_host_and_opponent(
+    deps: Deps,
+    env: Env,
+    host: String,
+    opponent: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<GetGamesResponse> {
+    let valid_host = deps.api.addr_validate(&host)?;
+    let valid_opp = deps.api.addr_validate(&opponent)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let order = order.unwrap_or(OrderBy::Ascending).into();
+    let (min, max) = page_bounds(start_after, order);
+
+    let (games_found, next_start_after) = scan_page(
+        games()
+            .idx
+            .host
+            .prefix(valid_host)
+            .range(deps.storage, min, max, order),
+        limit,
+        |game| game.opponent.as_ref() == Some(&valid_opp),
+    )?;
+
+    if games_found.is_empty() && start_after.is_none() && next_start_after.is_none() {
+        return Err(StdError::generic_err("No game found"));
+    }
+
+    Ok(GetGamesResponse {
+        games: reported_games(deps, &env, games_found)?,
+        next_start_after,
+    })
+}
+
+pub fn try_query_games_by_host(
+    deps: Deps,
+    env: Env,
+    host: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<GetGamesResponse> {
+    let valid_host = deps.api.addr_validate(&host)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let order = order.unwrap_or(OrderBy::Ascending).into();
+    let (min, max) = page_bounds(start_after, order);
+
+    let games = games()
+        .idx
+        .host
+        .prefix(valid_host)
+        .range(deps.storage, min, max, order)
+        .take(limit)
+        .map(|game| game.map(|(_, game)| game))
+        .collect::<StdResult<Vec<GameState>>>()?;
+
+    Ok(games_response(reported_games(deps, &env, games)?, limit))
+}
+
+pub fn try_query_games_by_opponent(
+    deps: Deps,
+    env: Env,
+    opponent: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<GetGamesResponse> {
+    let valid_opp = deps.api.addr_validate(&opponent)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let order = order.unwrap_or(OrderBy::Ascending).into();
+    let (min, max) = page_bounds(start_after, order);
+
+    let games = games()
+        .idx
+        .opponent
+        .prefix(valid_opp.to_string())
+        .range(deps.storage, min, max, order)
+        .take(limit)
+        .map(|game| game.map(|(_, game)| game))
+        .collect::<StdResult<Vec<GameState>>>()?;
+
+    Ok(games_response(reported_games(deps, &env, games)?, limit))
+}
+
+/// Unexpired games where it's `player`'s turn: responding to a challenge as the opponent,
+/// or revealing and committing moves as the host
+pub fn try_query_pending_challenges(
+    deps: Deps,
+    env: Env,
+    player: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<PendingChallengesResponse> {
+    let player = deps.api.addr_validate(&player)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let order = order.unwrap_or(OrderBy::Ascending).into();
+    let config = CONFIG.load(deps.storage)?;
+
+    let awaiting_player = |game: &GameState, as_host: bool| {
+        (game.status != GameStatus::AwaitingOpponent) == as_host
+            && !game_expires(&config, &env.block, game).is_expired(&env.block)
+    };
+
+    //each side is scanned up to a full page, the merged page is cut back down to size
+    let (min, max) = page_bounds(start_after, order);
+    let (mut pending, host_cursor) = scan_page(
+        games()
+            .idx
+            .host
+            .prefix(player.clone())
+            .range(deps.storage, min, max, order),
+        limit,
+        |game| awaiting_player(game, true),
+    )?;
+
+    let (min, max) = page_bounds(start_after, order);
+    let (opp_pending, opp_cursor) = scan_page(
+        games()
+            .idx
+            .opponent
+            .prefix(player.to_string())
+            .range(deps.storage, min, max, order),
+        limit,
+        |game| awaiting_player(game, false),
+    )?;
+    pending.extend(opp_pending);
+
+    pending.sort_by_key(|game| game.id);
+    if let Order::Descending = order {
+        pending.reverse();
+    }
+
+    //games past the side that stopped first haven't been scanned on the other side yet
+    let frontier = host_cursor
+        .into_iter()
+        .chain(opp_cursor)
+        .reduce(|a, b| match order {
+            Order::Ascending => a.min(b),
+            Order::Descending => a.max(b),
+        });
+    if let Some(frontier) = frontier {
+        pending.retain(|game| match order {
+            Order::Ascending => game.id <= frontier,
+            Order::Descending => game.id >= frontier,
+        });
+    }
+    let next_start_after = if pending.len() >= limit {
+        pending.truncate(limit);
+        pending.last().map(|game| game.id)
+    } else {
+        frontier
+    };
+
+    let challenges = pending
+        .into_iter()
+        .map(|game| PendingChallenge {
+            expires: game_expires(&config, &env.block, &game),
+            game_id: game.id,
+            status: game.status,
+            host: game.host,
+            opponent: game.opponent,
+            wager: game.host_wager,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(PendingChallengesResponse {
+        challenges,
+        next_start_after,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn try_query_open_challenges(
+    deps: Deps,
+    env: Env,
+    denom: String,
+    min_amount: Option<Uint128>,
+    max_amount: Option<Uint128>,
+    start_after: Option<OpenChallengeCursor>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<OpenChallengesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    //cheapest wagers first unless asked otherwise
+    let order = order.unwrap_or(OrderBy::Ascending).into();
+
+    let mut min = Bound::inclusive((min_amount.unwrap_or_default().u128(), 0));
+    let mut max = Bound::inclusive((max_amount.unwrap_or(Uint128::MAX).u128(), u64::MAX));
+
+    //challenges are keyed by wager amount, so the cursor carries it and resumes even
+    //after its game left the index
+    if let Some(cursor) = start_after {
+        let cursor = Bound::exclusive((cursor.amount.u128(), cursor.game_id));
+        match order {
+            Order::Ascending => min = cursor,
+            Order::Descending => max = cursor,
+        }
+    }
+
+    let keys = OPEN_CHALLENGES
+        .sub_prefix(&denom)
+        .keys(deps.storage, Some(min), Some(max), order)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let games = keys
+        .iter()
+        .map(|(_, game_id)| games().load(deps.storage, *game_id))
+        .collect::<StdResult<Vec<GameState>>>()?;
+
+    Ok(OpenChallengesResponse {
+        games: reported_games(deps, &env, games)?,
+        next_start_after: next_start_after(&keys, limit, |(amount, game_id)| OpenChallengeCursor {
+            amount: Uint128::new(*amount),
+            game_id: *game_id,
+        }),
+    })
+}
+
+pub fn try_query_history_by_player(
+    deps: Deps,
+    player: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<GetHistoryResponse> {
+    let player = deps.api.addr_validate(&player)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let order = order.unwrap_or(OrderBy::Descending).into();
+    let (min, max) = page_bounds(start_after, order);
+
+    let games = PLAYER_HISTORY
+        .prefix(&player)
+        .keys(deps.storage, min, max, order)
+        .take(limit)
+        .map(|game_id| game_id.and_then(|game_id| GAME_HISTORY.load(deps.storage, game_id)))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(history_response(games, limit))
+}
+
+pub fn try_query_recent_games(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<GetHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let order = order.unwrap_or(OrderBy::Descending).into();
+    let (min, max) = page_bounds(start_after, order);
+
+    let games = GAME_HISTORY
+        .range(deps.storage, min, max, order)
+        .take(limit)
+        .map(|item| item.map(|(_, game)| game))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(history_response(games, limit))
+}
+
+/// Range bounds that resume a list after `start_after` in the direction of `order`
+fn page_bounds<'a, K: PrimaryKey<'a>>(
+    start_after: Option<K>,
+    order: Order,
+) -> (Option<Bound<'a, K>>, Option<Bound<'a, K>>) {
+    let cursor = start_after.map(Bound::exclusive);
+    match order {
+        Order::Ascending => (cursor, None),
+        Order::Descending => (None, cursor),
+    }
+}
+
+/// Collects up to `limit` games that `keep` accepts, reading at most `MAX_SCAN` entries.
+/// The cursor is the last game read when the page filled up or the scan ran out, so sparse
+/// matches cost several short pages instead of one unbounded one.
+fn scan_page(
+    games: impl Iterator<Item = StdResult<(u64, GameState)>>,
+    limit: usize,
+    keep: impl Fn(&GameState) -> bool,
+) -> StdResult<(Vec<GameState>, Option<u64>)> {
+    let mut page = vec![];
+    let mut scanned = 0;
+    let mut last = None;
+
+    for game in games.take(MAX_SCAN) {
+        let (game_id, game) = game?;
+        scanned += 1;
+        last = Some(game_id);
+        if keep(&game) {
+            page.push(game);
+            if page.len() == limit {
+                return Ok((page, last));
+            }
+        }
+    }
+
+    let cursor = if scanned == MAX_SCAN { last } else { None };
+    Ok((page, cursor))
+}
+
+//a full page may be followed by more results, so it hands out a cursor
+fn next_start_after<T, K>(items: &[T], limit: usize, key: impl Fn(&T) -> K) -> Option<K> {
+    if items.len() < limit {
+        return None;
+    }
+    items.last().map(key)
+}
+
+//games report the deadline moves are actually accepted until, which a pause pushes back
+fn reported_games(deps: Deps, env: &Env, games: Vec<GameState>) -> StdResult<Vec<GameState>> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(games
+        .into_iter()
+        .map(|mut game| {
+            game.expires = game_expires(&config, &env.block, &game);
+            game
+        })
+        .collect())
+}
+
+fn games_response(games: Vec<GameState>, limit: usize) -> GetGamesResponse {
+    GetGamesResponse {
+        next_start_after: next_start_after(&games, limit, |game| game.id),
+        games,
+    }
+}
+
+fn history_response(games: Vec<SettledGame>, limit: usize) -> GetHistoryResponse {
+    GetHistoryResponse {
+        next_start_after: next_start_after(&games, limit, |settled| settled.game.id),
+        games,
+    }
+}
+
+pub fn try_query_player_stats(deps: Deps, address: String) -> StdResult<PlayerStatsResponse> {
+    let address = deps.api.addr_validate(&address)?;
+
+    let stats = player_stats()
+        .may_load(deps.storage, address.clone())?
+        .unwrap_or_else(|| PlayerStats::new(address));
+
+    player_stats_response(deps, stats)
+}
+
+pub fn try_query_top_players(
+    deps: Deps,
+    order_by: TopPlayersOrder,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<GetTopPlayersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    //players are ranked from the top down unless asked otherwise
+    let order = order.unwrap_or(OrderBy::Descending).into();
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    let stats: Vec<PlayerStats> = match order_by {
+        TopPlayersOrder::Wins => {
+            let cursor = match start_after {
+                Some(addr) => {
+                    let stats = player_stats().load(deps.storage, addr.clone())?;
+                    Some((stats.wins, addr))
+                }
+                None => None,
+            };
+            let (min, max) = page_bounds(cursor, order);
+
+            player_stats()
+                .idx
+                .wins
+                .range(deps.storage, min, max, order)
+                .take(limit)
+                .map(|item| item.map(|(_, stats)| stats))
+                .collect::<StdResult<_>>()?
+        }
+        TopPlayersOrder::NetWinnings { denom } => {
+            let cursor = match start_after {
+                Some(addr) => {
+                    let stats =
+                        player_denom_stats().load(deps.storage, (addr.clone(), denom.clone()))?;
+                    Some((stats.net_winnings(), (addr, denom.clone())))
+                }
+                None => None,
+            };
+            let (min, max) = page_bounds(cursor, order);
+
+            player_denom_stats()
+                .idx
+                .net_winnings
+                .sub_prefix(denom)
+                .range(deps.storage, min, max, order)
+                .take(limit)
+                .map(|item| {
+                    item.and_then(|(_, stats)| player_stats().load(deps.storage, stats.address))
+                })
+                .collect::<StdResult<_>>()?
+        }
+    };
+
+    let players = stats
+        .into_iter()
+        .map(|stats| player_stats_response(deps, stats))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(GetTopPlayersResponse {
+        next_start_after: next_start_after(&players, limit, |player| player.address.to_string()),
+        players,
+    })
+}
+
+fn player_stats_response(deps: Deps, stats: PlayerStats) -> StdResult<PlayerStatsResponse> {
+    let mut total_wagered = vec![];
+    let mut total_won = vec![];
+
+    for item in player_denom_stats().prefix(stats.address.clone()).range(
+        deps.storage,
+        None,
+        None,
+        Order::Ascending,
+    ) {
+        let (_, denom_stats) = item?;
+        total_wagered.push(Coin {
+            denom: denom_stats.denom.clone(),
+            amount: denom_stats.total_wagered,
+        });
+        total_won.push(Coin {
+            denom: denom_stats.denom,
+            amount: denom_stats.total_won,
+        });
+    }
+
+    Ok(PlayerStatsResponse {
+        address: stats.address,
+        wins: stats.wins,
+        losses: stats.losses,
+        ties: stats.ties,
+        total_wagered,
+        total_won,
+    })
+}
+
+pub fn try_query_rating(deps: Deps, address: String) -> StdResult<PlayerRating> {
+    let address = deps.api.addr_validate(&address)?;
+    load_rating(deps.storage, &address)
+}
+
+pub fn try_query_ratings(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<GetRatingsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    //highest rated first unless asked otherwise
+    let order = order.unwrap_or(OrderBy::Descending).into();
+
+    let cursor = match start_after {
+        Some(addr) => {
+            let addr = deps.api.addr_validate(&addr)?;
+            let rating = ratings().load(deps.storage, addr.clone())?;
+            Some((rating.rating, addr))
+        }
+        None => None,
+    };
+    let (min, max) = page_bounds(cursor, order);
+
+    let ratings = ratings()
+        .idx
+        .rating
+        .range(deps.storage, min, max, order)
+        .take(limit)
+        .map(|item| item.map(|(_, rating)| rating))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(GetRatingsResponse {
+        next_start_after: next_start_after(&ratings, limit, |rating| rating.address.to_string()),
+        ratings,
+    })
+}
+
+pub fn try_query_series(deps: Deps, game_id: u64) -> StdResult<SeriesResponse> {
+    let game = games().load(deps.storage, game_id)?;
+
+    Ok(SeriesResponse {
+        game_id,
+        status: game.status,
+        best_of: game.best_of,
+        host_score: game.host_score,
+        opp_score: game.opp_score,
+        rounds: game.rounds,
+    })
+}
+
+pub fn try_query_rule_sets(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    order: Option<OrderBy>,
+) -> StdResult<GetRuleSetsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let order = order.unwrap_or(OrderBy::Ascending).into();
+
+    let (min, max) = page_bounds(start_after.as_deref(), order);
+
+    //built-in rule sets aren't stored, they're merged into the page by name
+    let mut rule_sets = vec![
+        RuleSetResponse {
+            rule_set: RuleSet::Classic,
+            moves: CLASSIC_MOVES.iter().map(|m| m.to_string()).collect(),
+        },
+        RuleSetResponse {
+            rule_set: RuleSet::Rpsls,
+            moves: RPSLS_MOVES.iter().map(|m| m.to_string()).collect(),
+        },
+    ];
+    if let Some(cursor) = &start_after {
+        rule_sets.retain(|rule_set| {
+            let name = rule_set_to_string(&rule_set.rule_set);
+            match order {
+                Order::Ascending => name > cursor.as_str(),
+                Order::Descending => name < cursor.as_str(),
+            }
+        });
+    }
+
+    for item in RULE_SETS.range(deps.storage, min, max, order).take(limit) {
+        let (name, moves) = item?;
+        rule_sets.push(RuleSetResponse {
+            rule_set: RuleSet::Custom { name },
+            moves,
+        });
+    }
+
+    rule_sets.sort_by(|a, b| rule_set_to_string(&a.rule_set).cmp(rule_set_to_string(&b.rule_set)));
+    if let Order::Descending = order {
+        rule_sets.reverse();
+    }
+    rule_sets.truncate(limit);
+
+    Ok(GetRuleSetsResponse {
+        next_start_after: next_start_after(&rule_sets, limit, |rule_set| {
+            rule_set_to_string(&rule_set.rule_set).to_string()
+        }),
+        rule_sets,
+    })
+}
+
+pub fn try_query_house(deps: Deps) -> StdResult<HouseResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let bankroll = HOUSE_BANKROLL.may_load(deps.storage)?.unwrap_or_default();
+
+    let max_bet = bankroll
+        .iter()
+        .map(|coin| Coin {
+            denom: coin.denom.clone(),
+            amount: max_house_bet(&bankroll, &coin.denom, config.max_house_bet_bps),
+        })
+        .collect();
+
+    Ok(HouseResponse {
+        bankroll,
+        max_bet,
+        randomness_provider: config.randomness_provider,
+    })
 }
@@ -1,4 +1,5 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
+use cw_controllers::AdminError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,6 +7,9 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
 
@@ -15,12 +19,138 @@ pub enum ContractError {
     #[error("Host and opponent addresses cannot be the same: {val:?}")]
     HostAndOpponentCannotBeTheSame { val: String },
 
-    #[error("Host and opponent can only have one active game at a time")]
-    ActiveGameAlreadyExists {},
-
     #[error("Your wager amount must match the host's wager amount")]
     InsufficientWagerAmount {},
 
-    #[error("Game between host and opponent could not be found")]
+    #[error("Game could not be found")]
     GameNotFound {},
+
+    #[error("Move commitment must be a hex encoded sha256 hash")]
+    InvalidCommitment {},
+
+    #[error("Opponent has already responded to this game")]
+    OpponentAlreadyResponded {},
+
+    #[error("Opponent has not responded to this game yet")]
+    OpponentHasNotResponded {},
+
+    #[error("Revealed move and salt do not match the host's commitment")]
+    InvalidReveal {},
+
+    #[error("Series length must be an odd number of rounds up to {max}")]
+    InvalidBestOf { max: u32 },
+
+    #[error("Host has not committed to a move for the next round yet")]
+    HostHasNotCommitted {},
+
+    #[error("Host has already committed to a move for this round")]
+    HostAlreadyCommitted {},
+
+    #[error("Wager is already escrowed for this series")]
+    WagerAlreadyEscrowed {},
+
+    #[error("Move is not part of this game's rule set")]
+    InvalidMove {},
+
+    #[error("Rule set could not be found")]
+    RuleSetNotFound {},
+
+    #[error("A rule set with this name already exists")]
+    RuleSetAlreadyExists {},
+
+    #[error("Rule sets need an odd number of unique moves, between 3 and {max}")]
+    InvalidRuleSet { max: usize },
+
+    #[error("Tournaments need an entry fee and between 2 and {max} participants")]
+    InvalidTournament { max: u32 },
+
+    #[error("Payout table must have one share per place at most and add up to 10000 bps")]
+    InvalidPayouts {},
+
+    #[error("Tournament could not be found")]
+    TournamentNotFound {},
+
+    #[error("Only the organizer can do this")]
+    NotOrganizer {},
+
+    #[error("Tournament has already started")]
+    TournamentStarted {},
+
+    #[error("Already registered for this tournament")]
+    AlreadyRegistered {},
+
+    #[error("Tournament needs at least two participants")]
+    NotEnoughParticipants {},
+
+    #[error("Seasons need between 2 and {max} participants")]
+    InvalidSeason { max: u32 },
+
+    #[error("Season could not be found")]
+    SeasonNotFound {},
+
+    #[error("Season has already started")]
+    SeasonStarted {},
+
+    #[error("Season is full")]
+    SeasonFull {},
+
+    #[error("Season still has matches to play")]
+    SeasonNotOver {},
+
+    #[error("Season has already been finalized")]
+    SeasonFinished {},
+
+    #[error("Playing the house is disabled until a randomness provider is configured")]
+    HouseDisabled {},
+
+    #[error("Bets against the house are capped at {max_bps} bps of its bankroll")]
+    BetTooLarge { max_bps: u16 },
+
+    #[error("House bankroll is too small")]
+    InsufficientBankroll {},
+
+    #[error("Only the randomness provider can deliver randomness")]
+    NotRandomnessProvider {},
+
+    #[error("Randomness must be 32 bytes")]
+    InvalidRandomness {},
+
+    #[error("Game timeout must be greater than zero")]
+    InvalidTimeout {},
+
+    #[error("Game has expired")]
+    GameExpired {},
+
+    #[error("Game has not expired yet")]
+    GameNotExpired {},
+
+    #[error("Fee cannot be more than {max} basis points")]
+    InvalidFee { max: u16 },
+
+    #[error("Contract is paused")]
+    Paused {},
+
+    #[error("Cannot renounce the admin while the contract is paused")]
+    RenounceWhilePaused {},
+
+    #[error("Cannot renounce the admin while the house holds funds")]
+    RenounceWithBankroll {},
+
+    #[error("Wagers in {denom} are not accepted")]
+    DenomNotAllowed { denom: String },
+
+    #[error("Wagers in {denom} must be at least {min}")]
+    WagerTooSmall { denom: String, min: Uint128 },
+
+    #[error("Wagers in {denom} can be at most {max}")]
+    WagerTooLarge { denom: String, max: Uint128 },
+
+    #[error("Each allowed denom must be listed once, with a minimum no larger than its maximum")]
+    InvalidDenomLimits {},
+
+    #[error("Cannot migrate from a different contract: {name}")]
+    WrongContract { name: String },
+
+    #[error("Cannot migrate from newer version {version}")]
+    CannotDowngrade { version: String },
 }
@@ -0,0 +1,317 @@
+//! Events emitted over the lifetime of every game, so indexers can follow games
+//! without decoding execute messages. The chain prefixes event types with `wasm-`,
+//! e.g. `rps_game_started` shows up as `wasm-rps_game_started`.
+//!
+//! Every game starts with `rps_game_started` and ends with either `rps_game_settled`
+//! or `rps_game_cancelled`. In between, each change of turn has its own event:
+//! `rps_game_responded` when the opponent plays, `rps_round_settled` when the host
+//! reveals a round of a series or bracket match and `rps_move_committed` when the host
+//! commits to the next round.
+//!
+//! Attribute keys and value formats are part of the contract's API:
+//! - addresses are bech32 strings, `opponent` is `open` for open challenges and
+//!   `house` for games against the house
+//! - balances are comma separated `{amount}{denom}` coins sorted by denom, with the
+//!   token contract address as denom for cw20 tokens
+//! - moves use `GameMove::as_str`
+//! - results are `host_wins`, `opponent_wins` or `tie`, from the host's side
+//!
+//! Attribute values can't be empty, so a key is left out when there is nothing to
+//! report, e.g. `winner` on a tie or `fee` when no fee was taken.
+
+use cosmwasm_std::{Addr, Event};
+use cw20::Balance;
+
+use cw_utils::Expiration;
+
+use crate::state::{GameMove, GameResult, GameState, HouseGame, RuleSet};
+
+pub const GAME_STARTED: &str = "rps_game_started";
+pub const GAME_RESPONDED: &str = "rps_game_responded";
+pub const ROUND_SETTLED: &str = "rps_round_settled";
+pub const MOVE_COMMITTED: &str = "rps_move_committed";
+pub const GAME_SETTLED: &str = "rps_game_settled";
+pub const GAME_CANCELLED: &str = "rps_game_cancelled";
+
+/// A new game, emitted once per game id.
+///
+/// Keys: `game_id`, `mode` (`player`, `tournament`, `season` or `house`), `host`,
+/// `opponent`, `wager`, `commitment` (the host's hidden move, bracket and league hosts
+/// commit with `rps_move_committed` instead), `rule_set`, `best_of`, `expires` and, for
+/// bracket and league matches, `tournament_id` or `season_id`.
+pub fn game_started(game: &GameState) -> Event {
+    add_attributes(
+        Event::new(GAME_STARTED),
+        vec![
+            ("game_id", game.id.to_string()),
+            ("mode", game_mode(game).to_string()),
+            ("host", game.host.to_string()),
+            ("opponent", opponent_to_string(&game.opponent)),
+            ("wager", balance_to_string(&game.host_wager)),
+            ("commitment", game.host_move_commitment.clone()),
+            ("rule_set", rule_set_to_string(&game.rule_set).to_string()),
+            ("best_of", game.best_of.to_string()),
+            ("expires", game.expires.to_string()),
+            ("tournament_id", id_to_string(game.tournament_id)),
+            ("season_id", id_to_string(game.season_id)),
+        ],
+    )
+}
+
+/// A game against the house, which is settled once the randomness arrives
+pub fn house_game_started(game: &HouseGame) -> Event {
+    Event::new(GAME_STARTED)
+        .add_attribute("game_id", game.id.to_string())
+        .add_attribute("mode", "house")
+        .add_attribute("host", &game.player)
+        .add_attribute("opponent", "house")
+        .add_attribute(
+            "wager",
+            balance_to_string(&Balance::from(game.wager.clone())),
+        )
+        .add_attribute("rule_set", rule_set_to_string(&game.rule_set))
+        .add_attribute("best_of", "1")
+        .add_attribute("expires", game.expires.to_string())
+}
+
+/// The opponent played their move, which also accepts an open challenge and escrows
+/// the opponent's wager the first time round. It's the host's turn to reveal next.
+///
+/// Keys: `game_id`, `host`, `opponent`, `opp_move`, `opp_wager`, `round` (counting
+/// from 1, tied rounds included) and `expires`.
+pub fn game_responded(game: &GameState) -> Event {
+    add_attributes(
+        Event::new(GAME_RESPONDED),
+        vec![
+            ("game_id", game.id.to_string()),
+            ("host", game.host.to_string()),
+            ("opponent", opponent_to_string(&game.opponent)),
+            ("opp_move", move_to_string(&game.opp_move).to_string()),
+            (
+                "opp_wager",
+                game.opp_wager
+                    .as_ref()
+                    .map(balance_to_string)
+                    .unwrap_or_default(),
+            ),
+            ("round", (game.rounds.len() + 1).to_string()),
+            ("expires", game.expires.to_string()),
+        ],
+    )
+}
+
+/// The host revealed a round of a series or bracket match. Unless it decided the game,
+/// which is followed by `rps_game_settled`, it's the host's turn to commit next.
+///
+/// Keys: `game_id`, `round`, `host_move`, `opp_move`, `result` of the round,
+/// `host_score`, `opp_score` and `expires`, which is left out after the deciding round.
+pub fn round_settled(game: &GameState, expires: Option<Expiration>) -> Event {
+    let (host_move, opp_move, result) = match game.rounds.last() {
+        Some(round) => (
+            round.host_move.as_str(),
+            round.opp_move.as_str(),
+            result_to_string(&round.result),
+        ),
+        None => ("", "", ""),
+    };
+
+    add_attributes(
+        Event::new(ROUND_SETTLED),
+        vec![
+            ("game_id", game.id.to_string()),
+            ("round", game.rounds.len().to_string()),
+            ("host_move", host_move.to_string()),
+            ("opp_move", opp_move.to_string()),
+            ("result", result.to_string()),
+            ("host_score", game.host_score.to_string()),
+            ("opp_score", game.opp_score.to_string()),
+            (
+                "expires",
+                expires
+                    .map(|expires| expires.to_string())
+                    .unwrap_or_default(),
+            ),
+        ],
+    )
+}
+
+/// The host committed to their move for the next round, it's the opponent's turn next.
+///
+/// Keys: `game_id`, `host`, `opponent`, `commitment`, `round` and `expires`.
+pub fn move_committed(game: &GameState) -> Event {
+    Event::new(MOVE_COMMITTED)
+        .add_attribute("game_id", game.id.to_string())
+        .add_attribute("host", &game.host)
+        .add_attribute("opponent", opponent_to_string(&game.opponent))
+        .add_attribute("commitment", &game.host_move_commitment)
+        .add_attribute("round", (game.rounds.len() + 1).to_string())
+        .add_attribute("expires", game.expires.to_string())
+}
+
+/// Payouts of a finished game
+pub struct Settlement<'a> {
+    pub result: &'a GameResult,
+    pub host_payout: &'a Balance,
+    pub opp_payout: &'a Balance,
+    pub fee: &'a Balance,
+    pub fee_collector: &'a Addr,
+}
+
+/// A game that was played out or forfeited by timeout, emitted once per game id.
+///
+/// Keys: `game_id`, `host`, `opponent`, `host_move` and `opp_move` of the last round,
+/// `result`, `winner`, `host_score` and `opp_score` (rounds won in a
+/// series), `host_payout`, `opp_payout`, `fee` and `fee_collector`.
+pub fn game_settled(game: &GameState, opponent: &Addr, settlement: Settlement) -> Event {
+    let winner = match settlement.result {
+        GameResult::HostWins => game.host.to_string(),
+        GameResult::OpponentWins => opponent.to_string(),
+        GameResult::Tie => String::new(),
+    };
+    let fee = balance_to_string(settlement.fee);
+    let fee_collector = if fee.is_empty() {
+        String::new()
+    } else {
+        settlement.fee_collector.to_string()
+    };
+
+    add_attributes(
+        Event::new(GAME_SETTLED),
+        vec![
+            ("game_id", game.id.to_string()),
+            ("host", game.host.to_string()),
+            ("opponent", opponent.to_string()),
+            ("host_move", move_to_string(&game.host_move).to_string()),
+            ("opp_move", move_to_string(&game.opp_move).to_string()),
+            ("result", result_to_string(settlement.result).to_string()),
+            ("winner", winner),
+            ("host_score", game.host_score.to_string()),
+            ("opp_score", game.opp_score.to_string()),
+            ("host_payout", balance_to_string(settlement.host_payout)),
+            ("opp_payout", balance_to_string(settlement.opp_payout)),
+            ("fee", fee),
+            ("fee_collector", fee_collector),
+        ],
+    )
+}
+
+/// A game against the house, the house's payout is what goes back to the bankroll
+pub fn house_game_settled(
+    game: &HouseGame,
+    house_move: &str,
+    result: &GameResult,
+    payout: &Balance,
+    returned: &Balance,
+) -> Event {
+    let winner = match result {
+        GameResult::HostWins => game.player.to_string(),
+        GameResult::OpponentWins => "house".to_string(),
+        GameResult::Tie => String::new(),
+    };
+
+    add_attributes(
+        Event::new(GAME_SETTLED),
+        vec![
+            ("game_id", game.id.to_string()),
+            ("host", game.player.to_string()),
+            ("opponent", "house".to_string()),
+            ("host_move", game.player_move.as_str().to_string()),
+            ("opp_move", house_move.to_string()),
+            ("result", result_to_string(result).to_string()),
+            ("winner", winner),
+            ("host_score", "0".to_string()),
+            ("opp_score", "0".to_string()),
+            ("host_payout", balance_to_string(payout)),
+            ("opp_payout", balance_to_string(returned)),
+        ],
+    )
+}
+
+/// A game that ended without being played, the host's wager is refunded.
+///
+/// Keys: `game_id`, `host`, `opponent`, `refund` and `reason` (`cancelled` by the host,
+/// `expired` without an opponent, or `expired` without randomness for a house game).
+pub fn game_cancelled(game: &GameState, reason: &str) -> Event {
+    add_attributes(
+        Event::new(GAME_CANCELLED),
+        vec![
+            ("game_id", game.id.to_string()),
+            ("host", game.host.to_string()),
+            ("opponent", opponent_to_string(&game.opponent)),
+            ("refund", balance_to_string(&game.host_wager)),
+            ("reason", reason.to_string()),
+        ],
+    )
+}
+
+/// A game against the house the randomness never arrived for
+pub fn house_game_cancelled(game: &HouseGame) -> Event {
+    Event::new(GAME_CANCELLED)
+        .add_attribute("game_id", game.id.to_string())
+        .add_attribute("host", &game.player)
+        .add_attribute("opponent", "house")
+        .add_attribute(
+            "refund",
+            balance_to_string(&Balance::from(game.wager.clone())),
+        )
+        .add_attribute("reason", "expired")
+}
+
+//skips the attributes with nothing to report
+fn add_attributes(event: Event, attrs: Vec<(&str, String)>) -> Event {
+    event.add_attributes(attrs.into_iter().filter(|(_, value)| !value.is_empty()))
+}
+
+pub fn balance_to_string(balance: &Balance) -> String {
+    match balance {
+        Balance::Native(native) => native
+            .0
+            .iter()
+            .map(|coin| coin.to_string())
+            .collect::<Vec<_>>()
+            .join(","),
+        Balance::Cw20(token) if token.amount.is_zero() => String::new(),
+        Balance::Cw20(token) => format!("{}{}", token.amount, token.address),
+    }
+}
+
+pub fn result_to_string(result: &GameResult) -> &'static str {
+    match result {
+        GameResult::HostWins => "host_wins",
+        GameResult::OpponentWins => "opponent_wins",
+        GameResult::Tie => "tie",
+    }
+}
+
+fn game_mode(game: &GameState) -> &'static str {
+    if game.tournament_id.is_some() {
+        "tournament"
+    } else if game.season_id.is_some() {
+        "season"
+    } else {
+        "player"
+    }
+}
+
+pub fn opponent_to_string(opponent: &Option<Addr>) -> String {
+    opponent
+        .as_ref()
+        .map(Addr::to_string)
+        .unwrap_or_else(|| "open".to_string())
+}
+
+fn id_to_string(id: Option<u64>) -> String {
+    id.map(|id| id.to_string()).unwrap_or_default()
+}
+
+fn move_to_string(game_move: &Option<GameMove>) -> &str {
+    game_move.as_ref().map(GameMove::as_str).unwrap_or_default()
+}
+
+pub fn rule_set_to_string(rule_set: &RuleSet) -> &str {
+    match rule_set {
+        RuleSet::Classic => "classic",
+        RuleSet::Rpsls => "rpsls",
+        RuleSet::Custom { name } => name,
+    }
+}
@@ -1,47 +1,515 @@
-/* use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use cosmwasm_std::{
-    to_binary, Addr, CosmosMsg, CustomQuery, Querier, QuerierWrapper, StdResult, WasmMsg, WasmQuery,
+    to_binary, Addr, Binary, Coin, CosmosMsg, QuerierWrapper, StdResult, Uint128, WasmMsg,
 };
+use cw20::Cw20ExecuteMsg;
+use cw_controllers::AdminResponse;
+use cw_utils::Duration;
 
-use crate::msg::{CustomResponse, ExecuteMsg, QueryMsg};
+use crate::msg::{
+    AllowedDenomsResponse, ExecuteMsg, GetGamesResponse, GetHistoryResponse, GetRatingsResponse,
+    GetRuleSetsResponse, GetTopPlayersResponse, HouseResponse, OpenChallengeCursor,
+    OpenChallengesResponse, OrderBy, PendingChallengesResponse, PlayerStatsResponse, QueryMsg,
+    ReceiveMsg, SeriesResponse, TopPlayersOrder,
+};
+use crate::state::{
+    Config, DenomLimit, GameMove, GameState, HouseGame, PlayerRating, RuleSet, Season, Tournament,
+};
 
-/// CwTemplateContract is a wrapper around Addr that provides a lot of helpers
-/// for working with this. Rename it to your contract name.
+/// RpsContract is a wrapper around Addr that builds the messages and queries of a
+/// deployed rock paper scissors contract, for use from other contracts and tests.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct CwTemplateContract(pub Addr);
+pub struct RpsContract(pub Addr);
 
-impl CwTemplateContract {
+impl RpsContract {
     pub fn addr(&self) -> Addr {
         self.0.clone()
     }
 
-    pub fn call<T: Into<ExecuteMsg>>(&self, msg: T) -> StdResult<CosmosMsg> {
+    /// Executes `msg` on the contract with `funds` attached
+    pub fn call<T: Into<ExecuteMsg>>(&self, msg: T, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
         let msg = to_binary(&msg.into())?;
         Ok(WasmMsg::Execute {
             contract_addr: self.addr().into(),
             msg,
-            funds: vec![],
+            funds,
         }
         .into())
     }
 
-    /// Get Custom
-    pub fn custom_query<Q, T, CQ>(&self, querier: &Q, val: String) -> StdResult<CustomResponse>
-    where
-        Q: Querier,
-        T: Into<String>,
-        CQ: CustomQuery,
-    {
-        let msg = QueryMsg::CustomMsg { val };
-        let query = WasmQuery::Smart {
-            contract_addr: self.addr().into(),
-            msg: to_binary(&msg)?,
+    /// Wagers `funds`, which the opponent has to match
+    pub fn start_game(
+        &self,
+        opponent: Option<String>,
+        host_move_commitment: String,
+        timeout: Option<Duration>,
+        best_of: Option<u32>,
+        rule_set: Option<RuleSet>,
+        funds: Vec<Coin>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(
+            ExecuteMsg::StartGame {
+                opponent,
+                host_move_commitment,
+                timeout,
+                best_of,
+                rule_set,
+            },
+            funds,
+        )
+    }
+
+    /// `funds` has to match the host's wager
+    pub fn opponent_response(
+        &self,
+        game_id: u64,
+        opp_move: GameMove,
+        funds: Vec<Coin>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::OpponentResponse { game_id, opp_move }, funds)
+    }
+
+    /// Sends `amount` of the cw20 `token` to the contract, which wagers them as `msg` says
+    pub fn send_cw20(
+        &self,
+        token: impl Into<String>,
+        amount: Uint128,
+        msg: ReceiveMsg,
+    ) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: token.into(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: self.addr().into(),
+                amount,
+                msg: to_binary(&msg)?,
+            })?,
+            funds: vec![],
         }
-        .into();
-        let res: CustomResponse = QuerierWrapper::<CQ>::new(querier).query(&query)?;
-        Ok(res)
+        .into())
+    }
+
+    pub fn pause(&self) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::Pause {}, vec![])
+    }
+
+    pub fn unpause(&self) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::Unpause {}, vec![])
+    }
+
+    pub fn update_admin(&self, admin: Option<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::UpdateAdmin { admin }, vec![])
+    }
+
+    pub fn update_config(
+        &self,
+        fee_bps: Option<u16>,
+        fee_collector: Option<String>,
+        randomness_provider: Option<String>,
+        max_house_bet_bps: Option<u16>,
+        allowed_denoms: Option<Vec<DenomLimit>>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(
+            ExecuteMsg::UpdateConfig {
+                fee_bps,
+                fee_collector,
+                randomness_provider,
+                max_house_bet_bps,
+                allowed_denoms,
+            },
+            vec![],
+        )
+    }
+
+    pub fn clear_randomness_provider(&self) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ClearRandomnessProvider {}, vec![])
+    }
+
+    /// Bets `funds` against the house
+    pub fn play_house(
+        &self,
+        player_move: GameMove,
+        rule_set: Option<RuleSet>,
+        funds: Vec<Coin>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(
+            ExecuteMsg::PlayHouse {
+                player_move,
+                rule_set,
+            },
+            funds,
+        )
+    }
+
+    pub fn receive_randomness(&self, job_id: String, randomness: Binary) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ReceiveRandomness { job_id, randomness }, vec![])
+    }
+
+    /// Adds `funds` to the house bankroll
+    pub fn fund_house(&self, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::FundHouse {}, funds)
+    }
+
+    pub fn withdraw_house(&self, amount: Vec<Coin>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::WithdrawHouse { amount }, vec![])
+    }
+
+    pub fn register_rule_set(&self, name: String, moves: Vec<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RegisterRuleSet { name, moves }, vec![])
+    }
+
+    pub fn create_tournament(
+        &self,
+        entry_fee: Coin,
+        max_participants: u32,
+        payouts_bps: Vec<u16>,
+        rule_set: Option<RuleSet>,
+        timeout: Option<Duration>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(
+            ExecuteMsg::CreateTournament {
+                entry_fee,
+                max_participants,
+                payouts_bps,
+                rule_set,
+                timeout,
+            },
+            vec![],
+        )
+    }
+
+    /// `funds` has to be the entry fee
+    pub fn join_tournament(&self, tournament_id: u64, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::JoinTournament { tournament_id }, funds)
+    }
+
+    pub fn start_tournament(&self, tournament_id: u64) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::StartTournament { tournament_id }, vec![])
+    }
+
+    pub fn cancel_tournament(&self, tournament_id: u64) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::CancelTournament { tournament_id }, vec![])
+    }
+
+    pub fn create_season(
+        &self,
+        max_participants: u32,
+        payouts_bps: Vec<u16>,
+        rule_set: Option<RuleSet>,
+        timeout: Option<Duration>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(
+            ExecuteMsg::CreateSeason {
+                max_participants,
+                payouts_bps,
+                rule_set,
+                timeout,
+            },
+            vec![],
+        )
+    }
+
+    pub fn join_season(&self, season_id: u64) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::JoinSeason { season_id }, vec![])
+    }
+
+    /// Adds `funds` to the season's prize pool
+    pub fn fund_season(&self, season_id: u64, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::FundSeason { season_id }, funds)
+    }
+
+    pub fn start_season(&self, season_id: u64) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::StartSeason { season_id }, vec![])
+    }
+
+    pub fn finalize_season(&self, season_id: u64) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::FinalizeSeason { season_id }, vec![])
+    }
+
+    pub fn reveal_move(
+        &self,
+        game_id: u64,
+        host_move: GameMove,
+        salt: String,
+    ) -> StdResult<CosmosMsg> {
+        self.call(
+            ExecuteMsg::RevealMove {
+                game_id,
+                host_move,
+                salt,
+            },
+            vec![],
+        )
+    }
+
+    pub fn commit_move(&self, game_id: u64, host_move_commitment: String) -> StdResult<CosmosMsg> {
+        self.call(
+            ExecuteMsg::CommitMove {
+                game_id,
+                host_move_commitment,
+            },
+            vec![],
+        )
+    }
+
+    pub fn cancel_game(&self, game_id: u64) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::CancelGame { game_id }, vec![])
+    }
+
+    pub fn claim_timeout(&self, game_id: u64) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ClaimTimeout { game_id }, vec![])
+    }
+
+    /// Runs `msg` against the contract, the typed helpers below cover every `QueryMsg`
+    pub fn query<T: DeserializeOwned>(
+        &self,
+        querier: &QuerierWrapper,
+        msg: QueryMsg,
+    ) -> StdResult<T> {
+        querier.query_wasm_smart(self.addr(), &msg)
+    }
+
+    pub fn admin(&self, querier: &QuerierWrapper) -> StdResult<AdminResponse> {
+        self.query(querier, QueryMsg::Admin {})
+    }
+
+    pub fn config(&self, querier: &QuerierWrapper) -> StdResult<Config> {
+        self.query(querier, QueryMsg::GetConfig {})
+    }
+
+    pub fn allowed_denoms(&self, querier: &QuerierWrapper) -> StdResult<AllowedDenomsResponse> {
+        self.query(querier, QueryMsg::GetAllowedDenoms {})
+    }
+
+    pub fn house(&self, querier: &QuerierWrapper) -> StdResult<HouseResponse> {
+        self.query(querier, QueryMsg::GetHouse {})
+    }
+
+    pub fn house_game(&self, querier: &QuerierWrapper, game_id: u64) -> StdResult<HouseGame> {
+        self.query(querier, QueryMsg::GetHouseGame { game_id })
+    }
+
+    pub fn rule_sets(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    ) -> StdResult<GetRuleSetsResponse> {
+        self.query(
+            querier,
+            QueryMsg::GetRuleSets {
+                start_after,
+                limit,
+                order,
+            },
+        )
+    }
+
+    pub fn game(&self, querier: &QuerierWrapper, game_id: u64) -> StdResult<GameState> {
+        self.query(querier, QueryMsg::GetGame { game_id })
+    }
+
+    pub fn game_by_host_and_opponent(
+        &self,
+        querier: &QuerierWrapper,
+        host: String,
+        opponent: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    ) -> StdResult<GetGamesResponse> {
+        self.query(
+            querier,
+            QueryMsg::GetGameByHostAndOpponent {
+                host,
+                opponent,
+                start_after,
+                limit,
+                order,
+            },
+        )
+    }
+
+    pub fn games_by_host(
+        &self,
+        querier: &QuerierWrapper,
+        host: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    ) -> StdResult<GetGamesResponse> {
+        self.query(
+            querier,
+            QueryMsg::GetGamesByHost {
+                host,
+                start_after,
+                limit,
+                order,
+            },
+        )
+    }
+
+    pub fn games_by_opponent(
+        &self,
+        querier: &QuerierWrapper,
+        opponent: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    ) -> StdResult<GetGamesResponse> {
+        self.query(
+            querier,
+            QueryMsg::GetGamesByOpponent {
+                opponent,
+                start_after,
+                limit,
+                order,
+            },
+        )
+    }
+
+    pub fn pending_challenges(
+        &self,
+        querier: &QuerierWrapper,
+        player: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    ) -> StdResult<PendingChallengesResponse> {
+        self.query(
+            querier,
+            QueryMsg::GetPendingChallenges {
+                player,
+                start_after,
+                limit,
+                order,
+            },
+        )
+    }
+
+    pub fn tournament(
+        &self,
+        querier: &QuerierWrapper,
+        tournament_id: u64,
+    ) -> StdResult<Tournament> {
+        self.query(querier, QueryMsg::GetTournament { tournament_id })
+    }
+
+    pub fn season(&self, querier: &QuerierWrapper, season_id: u64) -> StdResult<Season> {
+        self.query(querier, QueryMsg::GetSeason { season_id })
+    }
+
+    pub fn series(&self, querier: &QuerierWrapper, game_id: u64) -> StdResult<SeriesResponse> {
+        self.query(querier, QueryMsg::GetSeries { game_id })
+    }
+
+    pub fn player_stats(
+        &self,
+        querier: &QuerierWrapper,
+        address: String,
+    ) -> StdResult<PlayerStatsResponse> {
+        self.query(querier, QueryMsg::GetPlayerStats { address })
+    }
+
+    pub fn top_players(
+        &self,
+        querier: &QuerierWrapper,
+        order_by: TopPlayersOrder,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    ) -> StdResult<GetTopPlayersResponse> {
+        self.query(
+            querier,
+            QueryMsg::GetTopPlayers {
+                order_by,
+                start_after,
+                limit,
+                order,
+            },
+        )
+    }
+
+    pub fn rating(&self, querier: &QuerierWrapper, address: String) -> StdResult<PlayerRating> {
+        self.query(querier, QueryMsg::GetRating { address })
+    }
+
+    pub fn ratings(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    ) -> StdResult<GetRatingsResponse> {
+        self.query(
+            querier,
+            QueryMsg::GetRatings {
+                start_after,
+                limit,
+                order,
+            },
+        )
+    }
+
+    pub fn history_by_player(
+        &self,
+        querier: &QuerierWrapper,
+        player: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    ) -> StdResult<GetHistoryResponse> {
+        self.query(
+            querier,
+            QueryMsg::GetHistoryByPlayer {
+                player,
+                start_after,
+                limit,
+                order,
+            },
+        )
+    }
+
+    pub fn recent_games(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    ) -> StdResult<GetHistoryResponse> {
+        self.query(
+            querier,
+            QueryMsg::GetRecentGames {
+                start_after,
+                limit,
+                order,
+            },
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn open_challenges(
+        &self,
+        querier: &QuerierWrapper,
+        denom: String,
+        min_amount: Option<Uint128>,
+        max_amount: Option<Uint128>,
+        start_after: Option<OpenChallengeCursor>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    ) -> StdResult<OpenChallengesResponse> {
+        self.query(
+            querier,
+            QueryMsg::GetOpenChallenges {
+                denom,
+                min_amount,
+                max_amount,
+                start_after,
+                limit,
+                order,
+            },
+        )
     }
 }
- */
@@ -1,23 +1,191 @@
 #[cfg(test)]
-
 mod tests {
-    use crate::msg::{GetGamesResponse, InstantiateMsg, QueryMsg};
-    use crate::state::GameMove;
-    use crate::{contract, msg::ExecuteMsg};
+    use crate::msg::{
+        GetGamesResponse, InstantiateMsg, MigrateMsg, OpenChallengesResponse, PlayerStatsResponse,
+        QueryMsg, RandomnessProviderMsg, ReceiveMsg,
+    };
+    use crate::state::{
+        GameMove, GameResult, LegacyGameState, LegacyLeaderboard, ADMIN, LEGACY_GAMES,
+        LEGACY_LEADERBOARD,
+    };
+    use crate::{
+        contract,
+        contract::{get_game_result, hash_move},
+        helpers::RpsContract,
+        msg::ExecuteMsg,
+        ContractError,
+    };
     use anyhow::Result;
-    use cosmwasm_std::{Addr, Coin, Empty, Uint128};
+    use cosmwasm_std::{
+        from_slice, to_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Empty, Env, Event,
+        MessageInfo, Response, StdResult, Uint128, WasmMsg,
+    };
+    use cw2::{set_contract_version, ContractVersion};
+    use cw20::{Balance, BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
     use cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
+    use cw_utils::Duration;
+    use serde::{Deserialize, Serialize};
 
     const USER: &str = "user";
     const OPPONENT: &str = "opponent";
+    const SALT: &str = "s3cr3t";
 
     pub fn contract_rps() -> Box<dyn Contract<Empty>> {
         let contract =
-            ContractWrapper::new(contract::execute, contract::instantiate, contract::query);
+            ContractWrapper::new(contract::execute, contract::instantiate, contract::query)
+                .with_migrate(contract::migrate);
 
         Box::new(contract)
     }
 
+    pub fn contract_cw20() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            cw20_base::contract::execute,
+            cw20_base::contract::instantiate,
+            cw20_base::contract::query,
+        );
+
+        Box::new(contract)
+    }
+
+    //stands in for a randomness beacon, answering every request straight away
+    //with all-zero randomness
+    pub fn contract_beacon() -> Box<dyn Contract<Empty>> {
+        fn execute(
+            _deps: DepsMut,
+            _env: Env,
+            info: MessageInfo,
+            msg: RandomnessProviderMsg,
+        ) -> StdResult<Response> {
+            let RandomnessProviderMsg::GetNextRandomness { job_id } = msg;
+            Ok(Response::new().add_message(WasmMsg::Execute {
+                contract_addr: info.sender.to_string(),
+                msg: to_binary(&ExecuteMsg::ReceiveRandomness {
+                    job_id,
+                    randomness: Binary::from(vec![0; 32]),
+                })?,
+                funds: vec![],
+            }))
+        }
+
+        fn instantiate(
+            _deps: DepsMut,
+            _env: Env,
+            _info: MessageInfo,
+            _msg: Empty,
+        ) -> StdResult<Response> {
+            Ok(Response::new())
+        }
+
+        fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+            to_binary(&Empty {})
+        }
+
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+
+    //execute interface of the v0.1.0 release
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    #[serde(rename_all = "snake_case")]
+    pub enum LegacyExecuteMsg {
+        StartGame {
+            opponent: String,
+            host_move: GameMove,
+        },
+        OpponentResponse {
+            host: String,
+            opp_move: GameMove,
+        },
+    }
+
+    //stands in for a v0.1.0 deployment, writing state the way that release did
+    pub fn contract_rps_v0_1_0() -> Box<dyn Contract<Empty>> {
+        fn execute(
+            deps: DepsMut,
+            _env: Env,
+            info: MessageInfo,
+            msg: LegacyExecuteMsg,
+        ) -> StdResult<Response> {
+            match msg {
+                LegacyExecuteMsg::StartGame {
+                    opponent,
+                    host_move,
+                } => {
+                    let game = LegacyGameState {
+                        host: info.sender.clone(),
+                        opponent: Addr::unchecked(&opponent),
+                        host_wager: info.funds,
+                        opp_wager: None,
+                        host_move,
+                        opp_move: None,
+                        result: None,
+                    };
+                    LEGACY_GAMES.save(deps.storage, (info.sender.as_str(), &opponent), &game)?;
+                    LEGACY_GAMES.save(deps.storage, (&opponent, info.sender.as_str()), &game)?;
+                    Ok(Response::new())
+                }
+                LegacyExecuteMsg::OpponentResponse { host, opp_move } => {
+                    let game = LEGACY_GAMES.load(deps.storage, (&host, info.sender.as_str()))?;
+                    let moves = ["rock", "scissors", "paper"].map(String::from);
+                    let result = get_game_result(&moves, &game.host_move, &opp_move).unwrap();
+
+                    let mut record = LEGACY_LEADERBOARD
+                        .may_load(deps.storage, (&host, info.sender.as_str()))?
+                        .unwrap_or(LegacyLeaderboard {
+                            host: game.host.clone(),
+                            opponent: game.opponent.clone(),
+                            host_score: None,
+                            opp_score: None,
+                            ties: None,
+                        });
+                    let score = match result {
+                        GameResult::HostWins => &mut record.host_score,
+                        GameResult::OpponentWins => &mut record.opp_score,
+                        GameResult::Tie => &mut record.ties,
+                    };
+                    *score = Some(score.unwrap_or_default() + Uint128::new(1));
+                    LEGACY_LEADERBOARD.save(
+                        deps.storage,
+                        (&host, info.sender.as_str()),
+                        &record,
+                    )?;
+
+                    //only one of the two copies was ever removed
+                    LEGACY_GAMES.remove(deps.storage, (&host, info.sender.as_str()));
+
+                    let winner = match result {
+                        GameResult::OpponentWins => info.sender.to_string(),
+                        _ => host,
+                    };
+                    Ok(Response::new().add_message(BankMsg::Send {
+                        to_address: winner,
+                        amount: vec![Coin {
+                            denom: "TNT".to_string(),
+                            amount: game.host_wager[0].amount + info.funds[0].amount,
+                        }],
+                    }))
+                }
+            }
+        }
+
+        fn instantiate(
+            deps: DepsMut,
+            _env: Env,
+            _info: MessageInfo,
+            _msg: Empty,
+        ) -> StdResult<Response> {
+            set_contract_version(deps.storage, "crates.io:rock-paper-scissors", "0.1.0")?;
+            ADMIN.set(deps, None)?;
+            Ok(Response::new())
+        }
+
+        fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+            to_binary(&Empty {})
+        }
+
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+
     pub fn mock_app() -> App {
         let init_amount = vec![Coin {
             denom: "TNT".to_string(),
@@ -72,7 +240,13 @@ mod tests {
             self.app.instantiate_contract(
                 self.contract_id,
                 Addr::unchecked(self.owner.to_string()),
-                &InstantiateMsg { admin: None },
+                &InstantiateMsg {
+                    admin: None,
+                    fee_bps: 0,
+                    fee_collector: None,
+                    randomness_provider: None,
+                    max_house_bet_bps: None,
+                },
                 &[],
                 "rps",
                 admin,
@@ -122,8 +296,11 @@ mod tests {
         //assert_eq!(contract_addr.clone(), Addr::unchecked("contract0"));
 
         let msg = ExecuteMsg::StartGame {
-            opponent: OPPONENT.to_string(),
-            host_move: GameMove::Rock,
+            opponent: Some(OPPONENT.to_string()),
+            host_move_commitment: hash_move(&GameMove::Rock, SALT),
+            timeout: None,
+            best_of: None,
+            rule_set: None,
         };
 
         let host_wager = vec![Coin {
@@ -135,16 +312,19 @@ mod tests {
             .execute(contract_addr.clone(), msg, host_wager.clone())
             .unwrap();
 
-        assert_eq!(res.events[1].attributes[4].value, "10TNT ".to_string());
+        assert_eq!(res.events[1].attributes[5].value, "10TNT ".to_string());
 
         let msg = QueryMsg::GetGameByHostAndOpponent {
             host: USER.to_string(),
             opponent: OPPONENT.to_string(),
+            start_after: None,
+            limit: None,
+            order: None,
         };
 
-        let res = suite.query(contract_addr.clone(), msg).unwrap();
+        let res = suite.query(contract_addr, msg).unwrap();
 
-        //println!("GET GAME BY HOST AND OPP: {:?}", res);
+        assert_eq!(res.games[0].host_move, None);
     }
 
     #[test]
@@ -153,8 +333,11 @@ mod tests {
         let contract_addr = suite.instantiate(None).unwrap();
 
         let msg = ExecuteMsg::StartGame {
-            opponent: OPPONENT.to_string(),
-            host_move: GameMove::Rock,
+            opponent: Some(OPPONENT.to_string()),
+            host_move_commitment: hash_move(&GameMove::Rock, SALT),
+            timeout: None,
+            best_of: None,
+            rule_set: None,
         };
 
         let host_wager = vec![Coin {
@@ -167,13 +350,13 @@ mod tests {
             .unwrap();
 
         let msg = ExecuteMsg::OpponentResponse {
-            host: suite.owner.clone().to_string(),
+            game_id: 1,
             opp_move: GameMove::Rock,
         };
 
         let opp_wager = host_wager.clone();
 
-        let res = suite
+        let _res = suite
             .app
             .execute_contract(
                 Addr::unchecked(OPPONENT.to_string()),
@@ -183,9 +366,15 @@ mod tests {
             )
             .unwrap();
 
-        //println!("END GAME: {:?}", res);
+        let msg = ExecuteMsg::RevealMove {
+            game_id: 1,
+            host_move: GameMove::Rock,
+            salt: SALT.to_string(),
+        };
 
-        assert_eq!(res.events[1].attributes[4].value, "tie".to_string());
+        let res = suite.execute(contract_addr, msg, vec![]).unwrap();
+
+        assert_eq!(res.events[1].attributes[5].value, "tie".to_string());
 
         let res = suite
             .app
@@ -201,12 +390,474 @@ mod tests {
         let res = suite
             .app
             .wrap()
-            .query_balance(
-                Addr::unchecked(OPPONENT.clone()),
-                opp_wager[0].denom.clone(),
-            )
+            .query_balance(Addr::unchecked(OPPONENT), opp_wager[0].denom.clone())
             .unwrap();
 
         assert_eq!(res.amount, Uint128::new(100));
     }
+
+    #[test]
+    fn test_claim_timeout() {
+        let mut suite = Suite::init().unwrap();
+        let contract_addr = suite.instantiate(None).unwrap();
+
+        let msg = ExecuteMsg::StartGame {
+            opponent: Some(OPPONENT.to_string()),
+            host_move_commitment: hash_move(&GameMove::Rock, SALT),
+            timeout: Some(Duration::Height(10)),
+            best_of: None,
+            rule_set: None,
+        };
+
+        let wager = vec![Coin {
+            denom: "TNT".to_string(),
+            amount: Uint128::new(10),
+        }];
+
+        let _res = suite
+            .execute(contract_addr.clone(), msg, wager.clone())
+            .unwrap();
+
+        let msg = ExecuteMsg::OpponentResponse {
+            game_id: 1,
+            opp_move: GameMove::Paper,
+        };
+
+        let _res = suite
+            .app
+            .execute_contract(
+                Addr::unchecked(OPPONENT),
+                contract_addr.clone(),
+                &msg,
+                &wager,
+            )
+            .unwrap();
+
+        //host sees they lost and never reveals
+        suite.app.update_block(|block| block.height += 11);
+
+        let msg = ExecuteMsg::ClaimTimeout { game_id: 1 };
+
+        let _res = suite
+            .app
+            .execute_contract(Addr::unchecked(OPPONENT), contract_addr, &msg, &[])
+            .unwrap();
+
+        let res = suite
+            .app
+            .wrap()
+            .query_balance(Addr::unchecked(OPPONENT), "TNT")
+            .unwrap();
+
+        assert_eq!(res.amount, Uint128::new(110));
+
+        let res = suite
+            .app
+            .wrap()
+            .query_balance(Addr::unchecked(suite.owner.clone()), "TNT")
+            .unwrap();
+
+        assert_eq!(res.amount, Uint128::new(90));
+    }
+
+    #[test]
+    fn test_cw20_wager() {
+        let mut suite = Suite::init().unwrap();
+        let contract_addr = suite.instantiate(None).unwrap();
+
+        let cw20_id = suite.app.store_code(contract_cw20());
+        let token_addr = suite
+            .app
+            .instantiate_contract(
+                cw20_id,
+                Addr::unchecked(USER),
+                &cw20_base::msg::InstantiateMsg {
+                    name: "Community Token".to_string(),
+                    symbol: "COMM".to_string(),
+                    decimals: 6,
+                    initial_balances: vec![
+                        Cw20Coin {
+                            address: USER.to_string(),
+                            amount: Uint128::new(100),
+                        },
+                        Cw20Coin {
+                            address: OPPONENT.to_string(),
+                            amount: Uint128::new(100),
+                        },
+                    ],
+                    mint: None,
+                    marketing: None,
+                },
+                &[],
+                "comm",
+                None,
+            )
+            .unwrap();
+
+        let send = |msg: &ReceiveMsg| Cw20ExecuteMsg::Send {
+            contract: contract_addr.to_string(),
+            amount: Uint128::new(10),
+            msg: to_binary(msg).unwrap(),
+        };
+
+        let _res = suite
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                token_addr.clone(),
+                &send(&ReceiveMsg::StartGame {
+                    opponent: None,
+                    host_move_commitment: hash_move(&GameMove::Scissors, SALT),
+                    timeout: None,
+                    best_of: None,
+                    rule_set: None,
+                }),
+                &[],
+            )
+            .unwrap();
+
+        //open cw20 challenges are listed under the token address
+        let res: OpenChallengesResponse = suite
+            .app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::GetOpenChallenges {
+                    denom: token_addr.to_string(),
+                    min_amount: None,
+                    max_amount: None,
+                    start_after: None,
+                    limit: None,
+                    order: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.games.len(), 1);
+
+        //native coins can't match a cw20 wager
+        let err = suite
+            .app
+            .execute_contract(
+                Addr::unchecked(OPPONENT),
+                contract_addr.clone(),
+                &ExecuteMsg::OpponentResponse {
+                    game_id: 1,
+                    opp_move: GameMove::Rock,
+                },
+                &[Coin {
+                    denom: "TNT".to_string(),
+                    amount: Uint128::new(10),
+                }],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap().to_string(),
+            ContractError::InsufficientWagerAmount {}.to_string()
+        );
+
+        let _res = suite
+            .app
+            .execute_contract(
+                Addr::unchecked(OPPONENT),
+                token_addr.clone(),
+                &send(&ReceiveMsg::OpponentResponse {
+                    game_id: 1,
+                    opp_move: GameMove::Rock,
+                }),
+                &[],
+            )
+            .unwrap();
+
+        let _res = suite
+            .execute(
+                contract_addr,
+                ExecuteMsg::RevealMove {
+                    game_id: 1,
+                    host_move: GameMove::Scissors,
+                    salt: SALT.to_string(),
+                },
+                vec![],
+            )
+            .unwrap();
+
+        let balance = |addr: &str| -> Uint128 {
+            let res: BalanceResponse = suite
+                .app
+                .wrap()
+                .query_wasm_smart(
+                    token_addr.clone(),
+                    &Cw20QueryMsg::Balance {
+                        address: addr.to_string(),
+                    },
+                )
+                .unwrap();
+            res.balance
+        };
+
+        assert_eq!(balance(USER), Uint128::new(90));
+        assert_eq!(balance(OPPONENT), Uint128::new(110));
+    }
+    #[test]
+    fn test_play_house() {
+        let mut suite = Suite::init().unwrap();
+
+        let beacon_id = suite.app.store_code(contract_beacon());
+        let beacon_addr = suite
+            .app
+            .instantiate_contract(
+                beacon_id,
+                Addr::unchecked(USER),
+                &Empty {},
+                &[],
+                "beacon",
+                None,
+            )
+            .unwrap();
+
+        let contract_addr = suite
+            .app
+            .instantiate_contract(
+                suite.contract_id,
+                Addr::unchecked(USER),
+                &InstantiateMsg {
+                    admin: Some(USER.to_string()),
+                    fee_bps: 0,
+                    fee_collector: None,
+                    randomness_provider: Some(beacon_addr.to_string()),
+                    max_house_bet_bps: Some(1_000),
+                },
+                &[],
+                "rps",
+                None,
+            )
+            .unwrap();
+
+        let tnt = |amount: u128| {
+            vec![Coin {
+                denom: "TNT".to_string(),
+                amount: Uint128::new(amount),
+            }]
+        };
+
+        let _res = suite
+            .execute(contract_addr.clone(), ExecuteMsg::FundHouse {}, tnt(100))
+            .unwrap();
+
+        //the beacon's all-zero randomness makes the house play rock
+        let res = suite
+            .app
+            .execute_contract(
+                Addr::unchecked(OPPONENT),
+                contract_addr.clone(),
+                &ExecuteMsg::PlayHouse {
+                    player_move: GameMove::Paper,
+                    rule_set: None,
+                },
+                &tnt(10),
+            )
+            .unwrap();
+        assert!(res.has_event(
+            &Event::new("wasm")
+                .add_attribute("house_move", "rock")
+                .add_attribute("game_result", "player_wins")
+        ));
+
+        let res = suite
+            .app
+            .wrap()
+            .query_balance(Addr::unchecked(OPPONENT), "TNT")
+            .unwrap();
+        assert_eq!(res.amount, Uint128::new(110));
+
+        let res = suite
+            .app
+            .wrap()
+            .query_balance(contract_addr, "TNT")
+            .unwrap();
+        assert_eq!(res.amount, Uint128::new(90));
+    }
+
+    #[test]
+    fn test_migrate_from_v0_1_0() {
+        let mut app = mock_app();
+        let legacy_id = app.store_code(contract_rps_v0_1_0());
+        let contract_id = app.store_code(contract_rps());
+
+        let contract_addr = app
+            .instantiate_contract(
+                legacy_id,
+                Addr::unchecked(USER),
+                &Empty {},
+                &[],
+                "rps",
+                Some(USER.to_string()),
+            )
+            .unwrap();
+
+        let wager = vec![Coin {
+            denom: "TNT".to_string(),
+            amount: Uint128::new(10),
+        }];
+
+        //one settled game, which leaves a stale mirror behind, and one still open
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &LegacyExecuteMsg::StartGame {
+                opponent: OPPONENT.to_string(),
+                host_move: GameMove::Rock,
+            },
+            &wager,
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(OPPONENT),
+            contract_addr.clone(),
+            &LegacyExecuteMsg::OpponentResponse {
+                host: USER.to_string(),
+                opp_move: GameMove::Paper,
+            },
+            &wager,
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &LegacyExecuteMsg::StartGame {
+                opponent: OPPONENT.to_string(),
+                host_move: GameMove::Scissors,
+            },
+            &wager,
+        )
+        .unwrap();
+
+        let res = app
+            .migrate_contract(
+                Addr::unchecked(USER),
+                contract_addr.clone(),
+                &MigrateMsg {},
+                contract_id,
+            )
+            .unwrap();
+        let wasm = res.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+        assert_eq!(wasm.attributes[2].value, "0.1.0");
+        assert_eq!(wasm.attributes[4].value, "1");
+        assert_eq!(wasm.attributes[5].value, "1");
+
+        let version = app
+            .wrap()
+            .query_wasm_raw(contract_addr.clone(), b"contract_info".to_vec())
+            .unwrap()
+            .unwrap();
+        let version: ContractVersion = from_slice(&version).unwrap();
+        assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+
+        //the leaderboard became per-player stats
+        let stats: PlayerStatsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::GetPlayerStats {
+                    address: OPPONENT.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!((stats.wins, stats.losses, stats.ties), (1, 0, 0));
+
+        //only the open game survives, stored once
+        let res: GetGamesResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::GetGamesByHost {
+                    host: USER.to_string(),
+                    start_after: None,
+                    limit: None,
+                    order: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.games.len(), 1);
+        assert_eq!(res.games[0].id, 1);
+        assert_eq!(res.games[0].host_wager, Balance::from(wager.clone()));
+
+        //and it can be finished with the new flow, revealing with an empty salt
+        app.execute_contract(
+            Addr::unchecked(OPPONENT),
+            contract_addr.clone(),
+            &ExecuteMsg::OpponentResponse {
+                game_id: 1,
+                opp_move: GameMove::Paper,
+            },
+            &wager,
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::RevealMove {
+                game_id: 1,
+                host_move: GameMove::Scissors,
+                salt: "".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let balance = app.wrap().query_balance(USER, "TNT").unwrap();
+        assert_eq!(balance.amount, Uint128::new(100));
+    }
+
+    #[test]
+    fn test_contract_helper() {
+        let mut suite = Suite::init().unwrap();
+        let rps = RpsContract(suite.instantiate(None).unwrap());
+
+        let wager = vec![Coin {
+            denom: "TNT".to_string(),
+            amount: Uint128::new(10),
+        }];
+
+        let msg = rps
+            .start_game(
+                Some(OPPONENT.to_string()),
+                hash_move(&GameMove::Rock, SALT),
+                None,
+                None,
+                None,
+                wager.clone(),
+            )
+            .unwrap();
+        let _res = suite.app.execute(Addr::unchecked(USER), msg).unwrap();
+
+        let res = rps
+            .pending_challenges(&suite.app.wrap(), OPPONENT.to_string(), None, None, None)
+            .unwrap();
+        assert_eq!(res.challenges.len(), 1);
+        let game_id = res.challenges[0].game_id;
+
+        let msg = rps
+            .opponent_response(game_id, GameMove::Scissors, wager)
+            .unwrap();
+        let _res = suite.app.execute(Addr::unchecked(OPPONENT), msg).unwrap();
+
+        let msg = rps
+            .reveal_move(game_id, GameMove::Rock, SALT.to_string())
+            .unwrap();
+        let _res = suite.app.execute(Addr::unchecked(USER), msg).unwrap();
+
+        let res = rps
+            .recent_games(&suite.app.wrap(), None, None, None)
+            .unwrap();
+        assert_eq!(res.games[0].game.id, game_id);
+        assert_eq!(res.games[0].game.result, Some(GameResult::HostWins));
+
+        let res = rps
+            .player_stats(&suite.app.wrap(), USER.to_string())
+            .unwrap();
+        assert_eq!(res.wins, 1);
+
+        let balance = suite.app.wrap().query_balance(USER, "TNT").unwrap();
+        assert_eq!(balance.amount, Uint128::new(110));
+    }
 }
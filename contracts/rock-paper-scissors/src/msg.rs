@@ -1,38 +1,465 @@
+use cosmwasm_schema::QueryResponses;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::state::{GameMove, GameState};
+use cosmwasm_std::{Addr, Binary, Coin, Order, Uint128};
+use cw20::{Balance, Cw20ReceiveMsg};
+use cw_controllers::AdminResponse;
+use cw_utils::{Duration, Expiration};
+
+use crate::state::{
+    Config, DenomLimit, GameMove, GameState, GameStatus, HouseGame, PlayerRating, Round, RuleSet,
+    Season, SettledGame, Tournament,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct InstantiateMsg {
     pub admin: Option<String>,
+    /// House fee taken from winning pots, in basis points
+    #[serde(default)]
+    pub fee_bps: u16,
+    /// Defaults to the instantiator
+    pub fee_collector: Option<String>,
+    /// Beacon contract that supplies the house's moves
+    pub randomness_provider: Option<String>,
+    /// Defaults to `contract::DEFAULT_MAX_HOUSE_BET_BPS`
+    pub max_house_bet_bps: Option<u16>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     StartGame {
-        opponent: String,
-        host_move: GameMove,
+        /// Leave empty to post an open challenge anyone can accept
+        opponent: Option<String>,
+        /// Hex encoded sha256 of the host's move name and a secret salt,
+        /// see `contract::hash_move`.
+        host_move_commitment: String,
+        /// Defaults to `contract::DEFAULT_GAME_TIMEOUT`
+        timeout: Option<Duration>,
+        /// Play a series of rounds for a single wager, must be odd, defaults to 1
+        best_of: Option<u32>,
+        /// Defaults to classic rock paper scissors
+        rule_set: Option<RuleSet>,
     },
     OpponentResponse {
-        host: String,
+        game_id: u64,
         opp_move: GameMove,
     },
+    /// Wager cw20 tokens, `msg` is a `ReceiveMsg`
+    Receive(Cw20ReceiveMsg),
+    /// Admin only, stops new games, responses and registrations. Reveals,
+    /// cancellations, timeout claims and payouts keep working, but game clocks stand
+    /// still so nobody forfeits a game they were locked out of.
+    Pause {},
+    /// Admin only
+    Unpause {},
+    /// Admin only, hands the contract over to `admin` or renounces it for good when empty.
+    /// Renouncing needs the contract unpaused and the house bankroll withdrawn.
+    UpdateAdmin {
+        admin: Option<String>,
+    },
+    /// Admin only, fields left empty keep their current value
+    UpdateConfig {
+        fee_bps: Option<u16>,
+        fee_collector: Option<String>,
+        randomness_provider: Option<String>,
+        max_house_bet_bps: Option<u16>,
+        /// Replaces the wager denom allowlist, an empty list accepts any denom
+        allowed_denoms: Option<Vec<DenomLimit>>,
+    },
+    /// Admin only, turns house mode off. House games still waiting on randomness can be
+    /// refunded with `ClaimTimeout` once they expire.
+    ClearRandomnessProvider {},
+    /// Bets the sent funds against the house, which plays a move picked by the
+    /// randomness provider once it calls back with `ReceiveRandomness`
+    PlayHouse {
+        player_move: GameMove,
+        rule_set: Option<RuleSet>,
+    },
+    /// Randomness provider only, settles the house game `job_id` was requested for
+    ReceiveRandomness {
+        job_id: String,
+        randomness: Binary,
+    },
+    /// Admin only, adds the sent funds to the house bankroll
+    FundHouse {},
+    /// Admin only
+    WithdrawHouse {
+        amount: Vec<Coin>,
+    },
+    /// Admin only, each move beats the next half of `moves` and loses to the rest
+    RegisterRuleSet {
+        name: String,
+        moves: Vec<String>,
+    },
+    /// Opens registration for a single-elimination bracket, the entry fees make up the prize pool
+    CreateTournament {
+        entry_fee: Coin,
+        max_participants: u32,
+        /// Share of the prize pool for 1st, 2nd, 3rd... place in basis points, players
+        /// knocked out in the same round split their places' shares
+        payouts_bps: Vec<u16>,
+        rule_set: Option<RuleSet>,
+        /// Time each player has to act in a match, defaults to `contract::DEFAULT_GAME_TIMEOUT`
+        timeout: Option<Duration>,
+    },
+    /// Registers by paying the entry fee, the bracket starts once it's full
+    JoinTournament {
+        tournament_id: u64,
+    },
+    /// Organizer only, seeds the bracket before it's full
+    StartTournament {
+        tournament_id: u64,
+    },
+    /// Organizer only, refunds every entry fee while registration is open
+    CancelTournament {
+        tournament_id: u64,
+    },
+    /// Admin only, opens registration for a round-robin league
+    CreateSeason {
+        max_participants: u32,
+        /// Share of the prize pool for 1st, 2nd, 3rd... place in basis points, players
+        /// level on points and wins split their places' shares
+        payouts_bps: Vec<u16>,
+        rule_set: Option<RuleSet>,
+        /// Time each player has to act in a match, defaults to `contract::DEFAULT_GAME_TIMEOUT`
+        timeout: Option<Duration>,
+    },
+    JoinSeason {
+        season_id: u64,
+    },
+    /// Adds the sent funds to the prize pool, anyone can sponsor a season until it's finalized
+    FundSeason {
+        season_id: u64,
+    },
+    /// Admin only, schedules a match between every pair of players
+    StartSeason {
+        season_id: u64,
+    },
+    /// Admin only, pays out the prize pool once every match has been settled
+    FinalizeSeason {
+        season_id: u64,
+    },
+    RevealMove {
+        game_id: u64,
+        host_move: GameMove,
+        salt: String,
+    },
+    /// Host's hidden move for the next round of a series
+    CommitMove {
+        game_id: u64,
+        host_move_commitment: String,
+    },
+    /// Withdraws a challenge the opponent hasn't accepted and refunds the host's wager
+    CancelGame {
+        game_id: u64,
+    },
+    /// Settles an expired game in favour of the player who did not stall: the host
+    /// is refunded if the challenge was never accepted, otherwise whoever was
+    /// waiting on the other player takes the pot. A house game the randomness never
+    /// arrived for is called off, the player is refunded and the house keeps its reserve.
+    ClaimTimeout {
+        game_id: u64,
+    },
 }
 
+/// Hook messages embedded in a cw20 `Send`, the sent tokens are the wager
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    StartGame {
+        opponent: Option<String>,
+        host_move_commitment: String,
+        timeout: Option<Duration>,
+        best_of: Option<u32>,
+        rule_set: Option<RuleSet>,
+    },
+    OpponentResponse {
+        game_id: u64,
+        opp_move: GameMove,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
+#[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    GetGameByHostAndOpponent { host: String, opponent: String },
-    GetGamesByHost { host: String },
+    #[returns(AdminResponse)]
+    Admin {},
+    #[returns(Config)]
+    GetConfig {},
+    /// Denoms `StartGame` accepts with their wager limits, empty when any denom is accepted
+    #[returns(AllowedDenomsResponse)]
+    GetAllowedDenoms {},
+    #[returns(HouseResponse)]
+    GetHouse {},
+    #[returns(HouseGame)]
+    GetHouseGame { game_id: u64 },
+    /// Built-in and registered rule sets ordered by name, ascending by default. `start_after`
+    /// is the last name of the previous page, `classic` and `rpsls` for the built-ins.
+    #[returns(GetRuleSetsResponse)]
+    GetRuleSets {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    },
+    #[returns(GameState)]
+    GetGame { game_id: u64 },
+    /// Open games between the two players, oldest first by default. `start_after` is the
+    /// last game id of the previous page. Only a bounded number of the host's games are
+    /// scanned per page, so a page can come back short while `next_start_after` is set.
+    #[returns(GetGamesResponse)]
+    GetGameByHostAndOpponent {
+        host: String,
+        opponent: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    },
+    /// Open games hosted by `host`, oldest first by default
+    #[returns(GetGamesResponse)]
+    GetGamesByHost {
+        host: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    },
+    /// Open games where `opponent` was challenged by name, oldest first by default
+    #[returns(GetGamesResponse)]
+    GetGamesByOpponent {
+        opponent: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    },
+    /// Unexpired games waiting on `player` to respond, reveal or commit, oldest first by
+    /// default. Open challenges anyone can accept aren't included. Like
+    /// `GetGameByHostAndOpponent` a page can come back short while `next_start_after` is set.
+    #[returns(PendingChallengesResponse)]
+    GetPendingChallenges {
+        player: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    },
+    #[returns(Tournament)]
+    GetTournament { tournament_id: u64 },
+    #[returns(Season)]
+    GetSeason { season_id: u64 },
+    /// Round scores and moves of a best-of series
+    #[returns(SeriesResponse)]
+    GetSeries { game_id: u64 },
+    /// Settled game record and wager totals, zeroes for players who never finished a game
+    #[returns(PlayerStatsResponse)]
+    GetPlayerStats { address: String },
+    /// Players ranked from the top down by default, `start_after` is the last address of the
+    /// previous page
+    #[returns(GetTopPlayersResponse)]
+    GetTopPlayers {
+        order_by: TopPlayersOrder,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    },
+    /// Elo rating, players who never finished a game start at `contract::INITIAL_RATING`
+    #[returns(PlayerRating)]
+    GetRating { address: String },
+    /// Players ranked by rating from the top down by default, `start_after` is the last
+    /// address of the previous page
+    #[returns(GetRatingsResponse)]
+    GetRatings {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    },
+    /// Settled games `player` took part in, newest first by default. `start_after` is the
+    /// last game id of the previous page.
+    #[returns(GetHistoryResponse)]
+    GetHistoryByPlayer {
+        player: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    },
+    /// Settled games of every player, newest first by default
+    #[returns(GetHistoryResponse)]
+    GetRecentGames {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    },
+    /// Open challenges with a wager in `denom` (a cw20 contract address for tokens),
+    /// cheapest first by default. `start_after` is the `next_start_after` of the previous
+    /// page, it stays valid after that game is accepted or cancelled.
+    #[returns(OpenChallengesResponse)]
+    GetOpenChallenges {
+        denom: String,
+        min_amount: Option<Uint128>,
+        max_amount: Option<Uint128>,
+        start_after: Option<OpenChallengeCursor>,
+        limit: Option<u32>,
+        order: Option<OrderBy>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct GetHistoryResponse {
+    pub games: Vec<SettledGame>,
+    /// Pass as `start_after` to fetch the next page, empty once there are no more results
+    pub next_start_after: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct GetGamesResponse {
     pub games: Vec<GameState>,
+    /// Pass as `start_after` to fetch the next page, empty once there are no more results
+    pub next_start_after: Option<u64>,
+}
+
+/// Position of an open challenge in the wager index, the last game of a page
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct OpenChallengeCursor {
+    pub amount: Uint128,
+    pub game_id: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct OpenChallengesResponse {
+    pub games: Vec<GameState>,
+    /// Pass as `start_after` to fetch the next page, empty once there are no more results
+    pub next_start_after: Option<OpenChallengeCursor>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PendingChallenge {
+    pub game_id: u64,
+    /// The opponent needs to respond while `awaiting_opponent`, otherwise it's the host's turn
+    pub status: GameStatus,
+    pub host: Addr,
+    pub opponent: Option<Addr>,
+    /// The host's wager, which the opponent has to match
+    pub wager: Balance,
+    /// The other player can claim the game once it expires
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PendingChallengesResponse {
+    pub challenges: Vec<PendingChallenge>,
+    /// Pass as `start_after` to fetch the next page, empty once there are no more results
+    pub next_start_after: Option<u64>,
+}
+
+/// Sort direction of list queries
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderBy {
+    Ascending,
+    Descending,
+}
+
+impl From<OrderBy> for Order {
+    fn from(order: OrderBy) -> Self {
+        match order {
+            OrderBy::Ascending => Order::Ascending,
+            OrderBy::Descending => Order::Descending,
+        }
+    }
+}
+
+/// Execute interface of the randomness beacon, modelled on nois-proxy. The beacon
+/// answers each request with `ExecuteMsg::ReceiveRandomness` carrying the same `job_id`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RandomnessProviderMsg {
+    GetNextRandomness { job_id: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AllowedDenomsResponse {
+    pub denoms: Vec<DenomLimit>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct HouseResponse {
+    pub bankroll: Vec<Coin>,
+    /// Largest bet the house accepts in each denom
+    pub max_bet: Vec<Coin>,
+    pub randomness_provider: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RuleSetResponse {
+    pub rule_set: RuleSet,
+    /// Each move beats the next half of the list, wrapping around
+    pub moves: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct GetRuleSetsResponse {
+    pub rule_sets: Vec<RuleSetResponse>,
+    /// Pass as `start_after` to fetch the next page, empty once there are no more results
+    pub next_start_after: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct SeriesResponse {
+    pub game_id: u64,
+    pub status: GameStatus,
+    pub best_of: u32,
+    pub host_score: u32,
+    pub opp_score: u32,
+    pub rounds: Vec<Round>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TopPlayersOrder {
+    Wins,
+    /// Total won minus total wagered in `denom`
+    NetWinnings {
+        denom: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PlayerStatsResponse {
+    pub address: Addr,
+    pub wins: u64,
+    pub losses: u64,
+    pub ties: u64,
+    /// One coin per denom, cw20 tokens use their contract address as denom
+    pub total_wagered: Vec<Coin>,
+    pub total_won: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct GetTopPlayersResponse {
+    pub players: Vec<PlayerStatsResponse>,
+    /// Pass as `start_after` to fetch the next page, empty once there are no more results
+    pub next_start_after: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct GetRatingsResponse {
+    pub ratings: Vec<PlayerRating>,
+    /// Pass as `start_after` to fetch the next page, empty once there are no more results
+    pub next_start_after: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
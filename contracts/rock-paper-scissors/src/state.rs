@@ -1,33 +1,367 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Coin, Uint128};
-use cw_storage_plus::Map;
+use cosmwasm_std::{Addr, BlockInfo, Coin, Empty, Timestamp, Uint128};
+use cw20::Balance;
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use cw_utils::{Duration, Expiration};
 
 use cw_controllers::Admin;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct GameState {
+    pub id: u64,
+    pub status: GameStatus,
     pub host: Addr,
-    pub opponent: Addr,
-    pub host_wager: Vec<Coin>,
-    pub opp_wager: Option<Vec<Coin>>,
-    pub host_move: GameMove,
+    /// `None` while an open challenge is waiting for someone to accept it
+    pub opponent: Option<Addr>,
+    pub host_wager: Balance,
+    pub opp_wager: Option<Balance>,
+    pub host_move_commitment: String,
+    pub host_move: Option<GameMove>,
     pub opp_move: Option<GameMove>,
     pub result: Option<GameResult>,
+    pub rule_set: RuleSet,
+    /// Set for bracket matches, which are played without a wager
+    pub tournament_id: Option<u64>,
+    /// Set for league matches, which are played without a wager
+    pub season_id: Option<u64>,
+    /// Number of rounds in the series, the first player to win a majority takes the pot
+    pub best_of: u32,
+    /// Finished rounds of a series, tied rounds don't count and are replayed
+    pub rounds: Vec<Round>,
+    pub host_score: u32,
+    pub opp_score: u32,
+    /// How long each player has to act, the clock restarts once the opponent responds
+    pub timeout: Duration,
+    /// Pushed back by the time the contract spends paused, see `contract::game_expires`
+    pub expires: Expiration,
+    /// Time the contract had spent paused when `expires` was set
+    #[serde(default)]
+    pub paused_offset: ChainTime,
+}
+
+/// A point on, or a span of, the chain's clock
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, JsonSchema)]
+pub struct ChainTime {
+    pub height: u64,
+    pub seconds: u64,
+}
+
+impl ChainTime {
+    pub fn now(block: &BlockInfo) -> Self {
+        ChainTime {
+            height: block.height,
+            seconds: block.time.seconds(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct Leaderboard {
-    pub host: Addr,
-    pub opponent: Addr,
-    pub host_score: Option<Uint128>,
-    pub opp_score: Option<Uint128>,
-    pub ties: Option<Uint128>,
+pub struct Tournament {
+    pub id: u64,
+    pub organizer: Addr,
+    pub status: TournamentStatus,
+    pub entry_fee: Coin,
+    pub max_participants: u32,
+    /// Share of the prize pool for each finishing place, in basis points
+    pub payouts_bps: Vec<u16>,
+    pub rule_set: RuleSet,
+    pub timeout: Duration,
+    /// In registration order, which is also the seeding of the first round
+    pub participants: Vec<Addr>,
+    pub round: u32,
+    /// Game ids of the current round's matches that are still being played
+    pub matches: Vec<u64>,
+    /// Players through to the next round
+    pub advanced: Vec<Addr>,
+    /// Players knocked out in each round
+    pub eliminated: Vec<Vec<Addr>>,
+    pub champion: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TournamentStatus {
+    Registration,
+    InProgress,
+    Finished,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Season {
+    pub id: u64,
+    pub status: SeasonStatus,
+    pub max_participants: u32,
+    /// Share of the prize pool for each place in the final standings, in basis points
+    pub payouts_bps: Vec<u16>,
+    pub rule_set: RuleSet,
+    pub timeout: Duration,
+    /// Funded by sponsors, paid out when the admin finalizes the season
+    pub prize_pool: Vec<Coin>,
+    /// Game ids of matches still being played
+    pub matches: Vec<u64>,
+    /// In registration order until the season is finalized, then by rank
+    pub standings: Vec<Standing>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Standing {
+    pub player: Addr,
+    pub points: u32,
+    pub wins: u32,
+    pub ties: u32,
+    pub losses: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SeasonStatus {
+    Registration,
+    InProgress,
+    Finished,
+}
+
+/// A bet against the house waiting for the randomness beacon to pick the house's move
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HouseGame {
+    pub id: u64,
+    pub player: Addr,
+    pub player_move: GameMove,
+    pub rule_set: RuleSet,
+    /// The house reserves a matching amount from its bankroll until the game is settled
+    pub wager: Vec<Coin>,
+    /// The player can get their wager back with `ClaimTimeout` if the randomness
+    /// hasn't arrived by then
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Round {
+    pub host_move: GameMove,
+    pub opp_move: GameMove,
+    pub result: GameResult,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// House fee taken from winning pots, in basis points
+    pub fee_bps: u16,
+    pub fee_collector: Addr,
+    /// Beacon that supplies the house's moves, playing the house is disabled without one
+    #[serde(default)]
+    pub randomness_provider: Option<Addr>,
+    /// Largest bet against the house as a share of its bankroll, in basis points
+    #[serde(default)]
+    pub max_house_bet_bps: u16,
+    /// Set by the admin to stop new games and responses, settling and refunding still works
+    #[serde(default)]
+    pub paused: bool,
+    /// When the current pause started
+    #[serde(default)]
+    pub paused_since: Option<ChainTime>,
+    /// Time spent paused before the current pause, game clocks stand still while paused
+    #[serde(default)]
+    pub paused_total: ChainTime,
+    /// Denoms games can be wagered in, sorted by denom. Any denom is accepted while empty.
+    #[serde(default)]
+    pub allowed_denoms: Vec<DenomLimit>,
+}
+
+/// Wager limits for one denom, cw20 tokens use their contract address as denom
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DenomLimit {
+    pub denom: String,
+    pub min: Uint128,
+    /// No upper limit when empty
+    pub max: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PlayerStats {
+    pub address: Addr,
+    pub wins: u64,
+    pub losses: u64,
+    pub ties: u64,
+}
+
+impl PlayerStats {
+    pub fn new(address: Addr) -> Self {
+        PlayerStats {
+            address,
+            wins: 0,
+            losses: 0,
+            ties: 0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PlayerRating {
+    pub address: Addr,
+    /// Elo rating
+    pub rating: u32,
+    pub games_played: u64,
+}
+
+/// Wager totals of a player in one denom, cw20 tokens use their contract address as denom
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PlayerDenomStats {
+    pub address: Addr,
+    pub denom: String,
+    pub total_wagered: Uint128,
+    /// Everything paid back from settled games, including refunded ties
+    pub total_won: Uint128,
+}
+
+impl PlayerDenomStats {
+    pub fn net_winnings(&self) -> i128 {
+        self.total_won.u128() as i128 - self.total_wagered.u128() as i128
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GameStatus {
+    AwaitingOpponent,
+    AwaitingReveal,
+    /// Between rounds of a series, waiting for the host's next move
+    AwaitingCommitment,
+}
+
+pub struct GameIndexes<'a> {
+    pub host: MultiIndex<'a, Addr, GameState, u64>,
+    pub opponent: MultiIndex<'a, String, GameState, u64>,
+}
+
+impl<'a> IndexList<GameState> for GameIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<GameState>> + '_> {
+        let v: Vec<&dyn Index<GameState>> = vec![&self.host, &self.opponent];
+        Box::new(v.into_iter())
+    }
 }
 
-pub const GAMES: Map<(&str, &str), GameState> = Map::new("games");
-pub const LEADERBOARD: Map<(&str, &str), Leaderboard> = Map::new("leaderboard");
+/// Open games keyed by game id, settled and cancelled games are removed
+pub fn games<'a>() -> IndexedMap<'a, u64, GameState, GameIndexes<'a>> {
+    let indexes = GameIndexes {
+        host: MultiIndex::new(
+            |g: &GameState| g.host.clone(),
+            "game_states",
+            "game_states__host",
+        ),
+        opponent: MultiIndex::new(
+            |g: &GameState| g.opponent.as_ref().map(Addr::to_string).unwrap_or_default(),
+            "game_states",
+            "game_states__opponent",
+        ),
+    };
+    IndexedMap::new("game_states", indexes)
+}
+
+/// Open challenges keyed by `(denom, amount, game_id)` for every coin of the host's wager,
+/// cw20 wagers use the token contract address as denom
+pub const OPEN_CHALLENGES: Map<(&str, u128, u64), Empty> = Map::new("open_challenges");
+
+/// A finished game as it stood when it was settled
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SettledGame {
+    pub game: GameState,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+/// Settled games keyed by game id, cancelled and refunded games aren't kept
+pub const GAME_HISTORY: Map<u64, SettledGame> = Map::new("game_history");
+
+/// Ids of the settled games each player took part in
+pub const PLAYER_HISTORY: Map<(&Addr, u64), Empty> = Map::new("player_history");
+
+/// Id of the most recently created game
+pub const GAME_COUNT: Item<u64> = Item::new("game_count");
+
+pub struct PlayerStatsIndexes<'a> {
+    pub wins: MultiIndex<'a, u64, PlayerStats, Addr>,
+}
+
+impl<'a> IndexList<PlayerStats> for PlayerStatsIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<PlayerStats>> + '_> {
+        let v: Vec<&dyn Index<PlayerStats>> = vec![&self.wins];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Win/loss record of every player with at least one settled game
+pub fn player_stats<'a>() -> IndexedMap<'a, Addr, PlayerStats, PlayerStatsIndexes<'a>> {
+    let indexes = PlayerStatsIndexes {
+        wins: MultiIndex::new(
+            |s: &PlayerStats| s.wins,
+            "player_stats",
+            "player_stats__wins",
+        ),
+    };
+    IndexedMap::new("player_stats", indexes)
+}
+
+pub struct PlayerDenomStatsIndexes<'a> {
+    pub net_winnings: MultiIndex<'a, (String, i128), PlayerDenomStats, (Addr, String)>,
+}
+
+impl<'a> IndexList<PlayerDenomStats> for PlayerDenomStatsIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<PlayerDenomStats>> + '_> {
+        let v: Vec<&dyn Index<PlayerDenomStats>> = vec![&self.net_winnings];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Wager totals keyed by `(player, denom)`, indexed by net winnings within each denom
+pub fn player_denom_stats<'a>(
+) -> IndexedMap<'a, (Addr, String), PlayerDenomStats, PlayerDenomStatsIndexes<'a>> {
+    let indexes = PlayerDenomStatsIndexes {
+        net_winnings: MultiIndex::new(
+            |s: &PlayerDenomStats| (s.denom.clone(), s.net_winnings()),
+            "player_denom_stats",
+            "player_denom_stats__net_winnings",
+        ),
+    };
+    IndexedMap::new("player_denom_stats", indexes)
+}
+
+pub struct RatingIndexes<'a> {
+    pub rating: MultiIndex<'a, u32, PlayerRating, Addr>,
+}
+
+impl<'a> IndexList<PlayerRating> for RatingIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<PlayerRating>> + '_> {
+        let v: Vec<&dyn Index<PlayerRating>> = vec![&self.rating];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Skill rating of every player with at least one settled game
+pub fn ratings<'a>() -> IndexedMap<'a, Addr, PlayerRating, RatingIndexes<'a>> {
+    let indexes = RatingIndexes {
+        rating: MultiIndex::new(|r: &PlayerRating| r.rating, "ratings", "ratings__rating"),
+    };
+    IndexedMap::new("ratings", indexes)
+}
+
+pub const TOURNAMENTS: Map<u64, Tournament> = Map::new("tournaments");
+
+/// Id of the most recently created tournament
+pub const TOURNAMENT_COUNT: Item<u64> = Item::new("tournament_count");
+
+pub const SEASONS: Map<u64, Season> = Map::new("seasons");
+
+/// Id of the most recently created season
+pub const SEASON_COUNT: Item<u64> = Item::new("season_count");
+
+/// Keyed by game id, ids are shared with `games()`
+pub const HOUSE_GAMES: Map<u64, HouseGame> = Map::new("house_games");
+
+/// Funds the house can still bet, amounts reserved for open house games are excluded
+pub const HOUSE_BANKROLL: Item<Vec<Coin>> = Item::new("house_bankroll");
+
+pub const CONFIG: Item<Config> = Item::new("config");
 
 pub const ADMIN: Admin = Admin::new("admin");
 
@@ -43,4 +377,63 @@ pub enum GameMove {
     Rock,
     Paper,
     Scissors,
+    Lizard,
+    Spock,
+    /// A move of a rule set registered by the admin
+    Custom(String),
 }
+
+impl GameMove {
+    pub fn as_str(&self) -> &str {
+        match self {
+            GameMove::Rock => "rock",
+            GameMove::Paper => "paper",
+            GameMove::Scissors => "scissors",
+            GameMove::Lizard => "lizard",
+            GameMove::Spock => "spock",
+            GameMove::Custom(name) => name,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleSet {
+    Classic,
+    /// Rock-paper-scissors-lizard-Spock
+    Rpsls,
+    /// Registered by the admin with `RegisterRuleSet`
+    Custom {
+        name: String,
+    },
+}
+
+/// Move names of every registered rule set. Each move beats the next half of the list,
+/// wrapping around, and loses to the rest.
+pub const RULE_SETS: Map<&str, Vec<String>> = Map::new("rule_sets");
+
+/// Game layout of v0.1.0, saved twice under `(host, opponent)` and `(opponent, host)`.
+/// Only read by `contract::migrate`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyGameState {
+    pub host: Addr,
+    pub opponent: Addr,
+    pub host_wager: Vec<Coin>,
+    pub opp_wager: Option<Vec<Coin>>,
+    pub host_move: GameMove,
+    pub opp_move: Option<GameMove>,
+    pub result: Option<GameResult>,
+}
+
+/// Head-to-head record of v0.1.0, only read by `contract::migrate`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyLeaderboard {
+    pub host: Addr,
+    pub opponent: Addr,
+    pub host_score: Option<Uint128>,
+    pub opp_score: Option<Uint128>,
+    pub ties: Option<Uint128>,
+}
+
+pub const LEGACY_GAMES: Map<(&str, &str), LegacyGameState> = Map::new("games");
+pub const LEGACY_LEADERBOARD: Map<(&str, &str), LegacyLeaderboard> = Map::new("leaderboard");
@@ -1,27 +1,55 @@
 #[cfg(test)]
 mod tests {
     use crate::{
-        contract::{execute, instantiate, query},
-        msg::{ExecuteMsg, GetGamesResponse, InstantiateMsg, QueryMsg},
-        state::GameMove,
+        contract::{
+            execute, expected_score, get_game_result, hash_move, instantiate, migrate, query,
+        },
+        events,
+        msg::{
+            AllowedDenomsResponse, ExecuteMsg, GetGamesResponse, GetHistoryResponse,
+            GetRatingsResponse, GetRuleSetsResponse, GetTopPlayersResponse, HouseResponse,
+            InstantiateMsg, MigrateMsg, OpenChallengeCursor, OpenChallengesResponse, OrderBy,
+            PendingChallengesResponse, PlayerStatsResponse, QueryMsg, RandomnessProviderMsg,
+            ReceiveMsg, SeriesResponse, TopPlayersOrder,
+        },
+        state::{
+            Config, DenomLimit, GameMove, GameResult, GameState, GameStatus, PlayerRating, RuleSet,
+            Season, SeasonStatus, Tournament, TournamentStatus,
+        },
         ContractError,
     };
+    use cosmwasm_schema::{generate_api, schema_for};
     use cosmwasm_std::{
-        from_binary,
+        attr, from_binary,
         testing::{mock_dependencies, mock_env, mock_info},
-        Coin, DepsMut, Response, StdError, Uint128,
+        testing::{MockApi, MockQuerier, MockStorage},
+        to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, DepsMut, Env, Event, OwnedDeps,
+        Response, StdError, Uint128, WasmMsg,
     };
+    use cw2::set_contract_version;
+    use cw20::Balance;
+    use cw_controllers::{AdminError, AdminResponse};
+    use cw_utils::{Duration, Expiration};
 
     const USER: &str = "user1";
     const DENOM: &str = "TNT";
     const AMOUNT: Uint128 = Uint128::new(100);
     const OPPONENT: &str = "opp1";
+    const SALT: &str = "s3cr3t";
+    const DEFAULT_GAME_TIMEOUT_SECS: u64 = 24 * 60 * 60;
+
+    fn default_instantiate_msg(admin: Option<&str>) -> InstantiateMsg {
+        InstantiateMsg {
+            admin: admin.map(String::from),
+            fee_bps: 0,
+            fee_collector: None,
+            randomness_provider: None,
+            max_house_bet_bps: None,
+        }
+    }
 
     fn instantiate_contract(deps: DepsMut) -> Response {
-        let msg = InstantiateMsg {
-            /* admin: Some(USER.to_string()), */
-            admin: None,
-        };
+        let msg = default_instantiate_msg(None);
         let info = mock_info(
             USER,
             &[
@@ -40,11 +68,14 @@ mod tests {
 
     fn start_game(deps: DepsMut) -> Result<Response, ContractError> {
         let msg = ExecuteMsg::StartGame {
-            opponent: OPPONENT.to_string(),
-            host_move: GameMove::Rock,
+            opponent: Some(OPPONENT.to_string()),
+            host_move_commitment: hash_move(&GameMove::Rock, SALT),
+            timeout: None,
+            best_of: None,
+            rule_set: None,
         };
         let info = mock_info(
-            &USER,
+            USER,
             &[Coin {
                 denom: DENOM.to_string(),
                 amount: AMOUNT,
@@ -56,11 +87,14 @@ mod tests {
 
     fn start_game_host_and_opp_same(deps: DepsMut) -> Result<Response, ContractError> {
         let msg = ExecuteMsg::StartGame {
-            opponent: USER.to_string(),
-            host_move: GameMove::Rock,
+            opponent: Some(USER.to_string()),
+            host_move_commitment: hash_move(&GameMove::Rock, SALT),
+            timeout: None,
+            best_of: None,
+            rule_set: None,
         };
         let info = mock_info(
-            &USER,
+            USER,
             &[Coin {
                 denom: DENOM.to_string(),
                 amount: AMOUNT,
@@ -72,14 +106,110 @@ mod tests {
 
     fn start_game_missing_funds(deps: DepsMut) -> Result<Response, ContractError> {
         let msg = ExecuteMsg::StartGame {
-            opponent: OPPONENT.to_string(),
-            host_move: GameMove::Rock,
+            opponent: Some(OPPONENT.to_string()),
+            host_move_commitment: hash_move(&GameMove::Rock, SALT),
+            timeout: None,
+            best_of: None,
+            rule_set: None,
         };
-        let info = mock_info(&USER, &[]);
+        let info = mock_info(USER, &[]);
 
         execute(deps, mock_env(), info, msg)
     }
 
+    //hosts a game against `opponent` and plays it through to the reveal
+    fn play_game(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        host: &str,
+        opponent: &str,
+        host_move: GameMove,
+        opp_move: GameMove,
+    ) -> Response {
+        let wager = [Coin {
+            denom: DENOM.to_string(),
+            amount: AMOUNT,
+        }];
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(host, &wager),
+            ExecuteMsg::StartGame {
+                opponent: Some(opponent.to_string()),
+                host_move_commitment: hash_move(&host_move, SALT),
+                timeout: None,
+                best_of: None,
+                rule_set: None,
+            },
+        )
+        .unwrap();
+        let game_id: u64 = res.attributes[1].value.parse().unwrap();
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(opponent, &wager),
+            ExecuteMsg::OpponentResponse { game_id, opp_move },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(host, &[]),
+            ExecuteMsg::RevealMove {
+                game_id,
+                host_move,
+                salt: SALT.to_string(),
+            },
+        )
+        .unwrap()
+    }
+
+    //plays a wager-free bracket or league match through to the reveal
+    fn play_match(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        game_id: u64,
+        host: &str,
+        opponent: &str,
+        host_move: GameMove,
+        opp_move: GameMove,
+    ) -> Response {
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(host, &[]),
+            ExecuteMsg::CommitMove {
+                game_id,
+                host_move_commitment: hash_move(&host_move, SALT),
+            },
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(opponent, &[]),
+            ExecuteMsg::OpponentResponse { game_id, opp_move },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(host, &[]),
+            ExecuteMsg::RevealMove {
+                game_id,
+                host_move,
+                salt: SALT.to_string(),
+            },
+        )
+        .unwrap()
+    }
+
+    fn env_after_timeout() -> Env {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(DEFAULT_GAME_TIMEOUT_SECS + 1);
+        env
+    }
+
     #[test]
     fn instantiate_test() {
         let mut deps = mock_dependencies();
@@ -105,13 +235,12 @@ mod tests {
         }
 
         let res = start_game(deps.as_mut()).unwrap();
-        assert_eq!(res.attributes[2].value, "opp1");
+        assert_eq!(res.attributes[1].value, "1");
+        assert_eq!(res.attributes[3].value, "opp1");
 
-        let res = start_game(deps.as_mut());
-        match res {
-            Err(ContractError::ActiveGameAlreadyExists {}) => {}
-            _ => panic!("Should error here"),
-        }
+        //the same pair can play several games at once
+        let res = start_game(deps.as_mut()).unwrap();
+        assert_eq!(res.attributes[1].value, "2");
     }
 
     #[test]
@@ -119,11 +248,14 @@ mod tests {
         let mut deps = mock_dependencies();
         let _res = instantiate_contract(deps.as_mut());
         let res = start_game(deps.as_mut()).unwrap();
-        assert_eq!(res.attributes[2].value, "opp1");
+        assert_eq!(res.attributes[3].value, "opp1");
 
         let query_msg = QueryMsg::GetGameByHostAndOpponent {
             host: USER.to_string(),
             opponent: OPPONENT.to_string(),
+            start_after: None,
+            limit: None,
+            order: None,
         };
         let res = query(deps.as_ref(), mock_env(), query_msg.clone()).unwrap();
         let value: GetGamesResponse = from_binary(&res).unwrap();
@@ -131,7 +263,7 @@ mod tests {
         assert_eq!(value.games.len(), 1);
 
         let response_msg = ExecuteMsg::OpponentResponse {
-            host: USER.to_string(),
+            game_id: 1,
             opp_move: GameMove::Paper,
         };
         let info = mock_info(
@@ -142,8 +274,22 @@ mod tests {
             }],
         );
         let res = execute(deps.as_mut(), mock_env(), info, response_msg).unwrap();
-        println!("RESPONSE RES: {:?}", res);
-        //assert_eq!(res.attributes[3].value, "opponent_wins");
+        assert_eq!(res.messages.len(), 0);
+
+        //game stays open until the host reveals
+        let res = query(deps.as_ref(), mock_env(), query_msg.clone()).unwrap();
+        let value: GetGamesResponse = from_binary(&res).unwrap();
+        assert_eq!(value.games[0].opp_move, Some(GameMove::Paper));
+        assert_eq!(value.games[0].host_move, None);
+
+        let reveal_msg = ExecuteMsg::RevealMove {
+            game_id: 1,
+            host_move: GameMove::Rock,
+            salt: SALT.to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), reveal_msg).unwrap();
+        assert_eq!(res.attributes[4].value, "opponent_wins");
+        assert_eq!(res.messages.len(), 1);
 
         let res = query(deps.as_ref(), mock_env(), query_msg);
 
@@ -153,6 +299,90 @@ mod tests {
         }
     }
 
+    #[test]
+    fn reveal_test() {
+        let mut deps = mock_dependencies();
+        let _res = instantiate_contract(deps.as_mut());
+        let _res = start_game(deps.as_mut()).unwrap();
+
+        let reveal_msg = ExecuteMsg::RevealMove {
+            game_id: 1,
+            host_move: GameMove::Rock,
+            salt: SALT.to_string(),
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            reveal_msg.clone(),
+        );
+        match res {
+            Err(ContractError::OpponentHasNotResponded {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let response_msg = ExecuteMsg::OpponentResponse {
+            game_id: 1,
+            opp_move: GameMove::Scissors,
+        };
+        let info = mock_info(
+            OPPONENT,
+            &[Coin {
+                amount: AMOUNT,
+                denom: DENOM.to_string(),
+            }],
+        );
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            response_msg.clone(),
+        )
+        .unwrap();
+
+        let res = execute(deps.as_mut(), mock_env(), info, response_msg);
+        match res {
+            Err(ContractError::OpponentAlreadyResponded {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        //host tries to change their move after seeing the opponent's
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::RevealMove {
+                game_id: 1,
+                host_move: GameMove::Paper,
+                salt: SALT.to_string(),
+            },
+        );
+        match res {
+            Err(ContractError::InvalidReveal {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        //opponent can't reveal on the host's behalf
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[]),
+            ExecuteMsg::RevealMove {
+                game_id: 1,
+                host_move: GameMove::Rock,
+                salt: SALT.to_string(),
+            },
+        );
+        match res {
+            Err(ContractError::GameNotFound {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info(USER, &[]), reveal_msg).unwrap();
+        assert_eq!(res.attributes[4].value, "host_wins");
+    }
+
     #[test]
     fn query_game_by_host_and_opp_test() {
         let mut deps = mock_dependencies();
@@ -161,6 +391,9 @@ mod tests {
         let query_msg = QueryMsg::GetGameByHostAndOpponent {
             host: USER.to_string(),
             opponent: OPPONENT.to_string(),
+            start_after: None,
+            limit: None,
+            order: None,
         };
 
         let res = query(deps.as_ref(), mock_env(), query_msg.clone());
@@ -178,7 +411,11 @@ mod tests {
 
         //print!("VALUE: {:?}", value);
 
-        assert_eq!(value.games[0].host_move, GameMove::Rock);
+        assert_eq!(
+            value.games[0].host_move_commitment,
+            hash_move(&GameMove::Rock, SALT)
+        );
+        assert_eq!(value.games[0].host_move, None);
     }
 
     #[test]
@@ -198,14 +435,41 @@ mod tests {
                 }],
             ),
             ExecuteMsg::StartGame {
-                opponent: "other_guy".to_string(),
-                host_move: GameMove::Paper,
+                opponent: Some("other_guy".to_string()),
+                host_move_commitment: hash_move(&GameMove::Paper, SALT),
+                timeout: None,
+                best_of: None,
+                rule_set: None,
+            },
+        )
+        .unwrap();
+
+        //games where the user is only the opponent aren't listed
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(
+                OPPONENT,
+                &[Coin {
+                    denom: DENOM.to_string(),
+                    amount: AMOUNT,
+                }],
+            ),
+            ExecuteMsg::StartGame {
+                opponent: Some(USER.to_string()),
+                host_move_commitment: hash_move(&GameMove::Scissors, SALT),
+                timeout: None,
+                best_of: None,
+                rule_set: None,
             },
         )
         .unwrap();
 
         let query_msg = QueryMsg::GetGamesByHost {
             host: USER.to_string(),
+            start_after: None,
+            limit: None,
+            order: None,
         };
 
         let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
@@ -215,5 +479,2941 @@ mod tests {
         print!("VALUE: {:?}", value);
 
         assert_eq!(value.games.len(), 2);
+        assert_eq!(value.games[0].id, 1);
+        assert_eq!(value.games[1].id, 2);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGame { game_id: 3 }).unwrap();
+        let value: GameState = from_binary(&res).unwrap();
+        assert_eq!(value.host, OPPONENT);
+        assert_eq!(value.status, GameStatus::AwaitingOpponent);
+    }
+
+    #[test]
+    fn claim_timeout_test() {
+        let mut deps = mock_dependencies();
+        let _res = instantiate_contract(deps.as_mut());
+        let _res = start_game(deps.as_mut()).unwrap();
+
+        let claim_msg = ExecuteMsg::ClaimTimeout { game_id: 1 };
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            claim_msg.clone(),
+        );
+        match res {
+            Err(ContractError::GameNotExpired {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let response_msg = ExecuteMsg::OpponentResponse {
+            game_id: 1,
+            opp_move: GameMove::Paper,
+        };
+        let opp_info = mock_info(
+            OPPONENT,
+            &[Coin {
+                amount: AMOUNT,
+                denom: DENOM.to_string(),
+            }],
+        );
+        let res = execute(
+            deps.as_mut(),
+            env_after_timeout(),
+            opp_info.clone(),
+            response_msg.clone(),
+        );
+        match res {
+            Err(ContractError::GameExpired {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        //unanswered challenge refunds the host
+        let res = execute(
+            deps.as_mut(),
+            env_after_timeout(),
+            mock_info(OPPONENT, &[]),
+            claim_msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[4].value, "refund");
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER.to_string(),
+                amount: vec![Coin {
+                    amount: AMOUNT,
+                    denom: DENOM.to_string(),
+                }],
+            })
+        );
+
+        //host that never reveals forfeits the pot
+        let _res = start_game(deps.as_mut()).unwrap();
+        let response_msg = ExecuteMsg::OpponentResponse {
+            game_id: 2,
+            opp_move: GameMove::Paper,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), opp_info, response_msg).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env_after_timeout(),
+            mock_info(USER, &[]),
+            ExecuteMsg::RevealMove {
+                game_id: 2,
+                host_move: GameMove::Rock,
+                salt: SALT.to_string(),
+            },
+        );
+        match res {
+            Err(ContractError::GameExpired {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            env_after_timeout(),
+            mock_info(OPPONENT, &[]),
+            ExecuteMsg::ClaimTimeout { game_id: 2 },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[4].value, "opponent_wins");
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: OPPONENT.to_string(),
+                amount: vec![Coin {
+                    amount: AMOUNT + AMOUNT,
+                    denom: DENOM.to_string(),
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn cancel_game_test() {
+        let mut deps = mock_dependencies();
+        let _res = instantiate_contract(deps.as_mut());
+        let _res = start_game(deps.as_mut()).unwrap();
+
+        let cancel_msg = ExecuteMsg::CancelGame { game_id: 1 };
+
+        //only the host can cancel
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[]),
+            cancel_msg.clone(),
+        );
+        match res {
+            Err(ContractError::GameNotFound {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            cancel_msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER.to_string(),
+                amount: vec![Coin {
+                    amount: AMOUNT,
+                    denom: DENOM.to_string(),
+                }],
+            })
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGame { game_id: 1 });
+        match res {
+            Err(StdError::NotFound { kind: _ }) => {}
+            _ => panic!("Should error here"),
+        }
+        let _res = start_game(deps.as_mut()).unwrap();
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(
+                OPPONENT,
+                &[Coin {
+                    amount: AMOUNT,
+                    denom: DENOM.to_string(),
+                }],
+            ),
+            ExecuteMsg::OpponentResponse {
+                game_id: 2,
+                opp_move: GameMove::Rock,
+            },
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::CancelGame { game_id: 2 },
+        );
+        match res {
+            Err(ContractError::OpponentAlreadyResponded {}) => {}
+            _ => panic!("Should error here"),
+        }
+    }
+
+    #[test]
+    fn open_challenge_test() {
+        let mut deps = mock_dependencies();
+        let _res = instantiate_contract(deps.as_mut());
+
+        for amount in [300u128, 100, 200] {
+            let _res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(
+                    USER,
+                    &[Coin {
+                        denom: DENOM.to_string(),
+                        amount: Uint128::new(amount),
+                    }],
+                ),
+                ExecuteMsg::StartGame {
+                    opponent: None,
+                    host_move_commitment: hash_move(&GameMove::Rock, SALT),
+                    timeout: None,
+                    best_of: None,
+                    rule_set: None,
+                },
+            )
+            .unwrap();
+        }
+
+        let query_msg = QueryMsg::GetOpenChallenges {
+            denom: DENOM.to_string(),
+            min_amount: Some(Uint128::new(150)),
+            max_amount: None,
+            start_after: None,
+            limit: None,
+            order: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), query_msg.clone()).unwrap();
+        let value: OpenChallengesResponse = from_binary(&res).unwrap();
+        let ids: Vec<u64> = value.games.iter().map(|game| game.id).collect();
+        assert_eq!(ids, vec![3, 1]);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetOpenChallenges {
+                denom: "LUNA".to_string(),
+                min_amount: None,
+                max_amount: None,
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: OpenChallengesResponse = from_binary(&res).unwrap();
+        assert_eq!(value.games.len(), 0);
+
+        //host can't accept their own challenge
+        let response_msg = ExecuteMsg::OpponentResponse {
+            game_id: 3,
+            opp_move: GameMove::Paper,
+        };
+        let wager = [Coin {
+            denom: DENOM.to_string(),
+            amount: Uint128::new(200),
+        }];
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &wager),
+            response_msg.clone(),
+        );
+        match res {
+            Err(ContractError::HostAndOpponentCannotBeTheSame { val: _ }) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &wager),
+            response_msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[3].value, OPPONENT);
+
+        //accepted challenges leave the lobby and can't be taken twice
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let value: GetGamesResponse = from_binary(&res).unwrap();
+        assert_eq!(value.games.len(), 1);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("other_guy", &wager),
+            response_msg,
+        );
+        match res {
+            Err(ContractError::OpponentAlreadyResponded {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGame { game_id: 3 }).unwrap();
+        let value: GameState = from_binary(&res).unwrap();
+        assert_eq!(value.opponent, Some(Addr::unchecked(OPPONENT)));
+        assert_eq!(value.status, GameStatus::AwaitingReveal);
+    }
+
+    #[test]
+    fn multi_denom_wager_test() {
+        let mut deps = mock_dependencies();
+        let _res = instantiate_contract(deps.as_mut());
+
+        let luna = |amount: u128| Coin {
+            denom: "LUNA".to_string(),
+            amount: Uint128::new(amount),
+        };
+        let tnt = |amount: u128| Coin {
+            denom: DENOM.to_string(),
+            amount: Uint128::new(amount),
+        };
+
+        //duplicate denoms are merged into a single coin
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[tnt(60), luna(5), tnt(40)]),
+            ExecuteMsg::StartGame {
+                opponent: Some(OPPONENT.to_string()),
+                host_move_commitment: hash_move(&GameMove::Rock, SALT),
+                timeout: None,
+                best_of: None,
+                rule_set: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[4].value, "5LUNA 100TNT ");
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[tnt(100)]),
+            ExecuteMsg::OpponentResponse {
+                game_id: 1,
+                opp_move: GameMove::Scissors,
+            },
+        );
+        match res {
+            Err(ContractError::InsufficientWagerAmount {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        //coin order doesn't matter
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[tnt(100), luna(5)]),
+            ExecuteMsg::OpponentResponse {
+                game_id: 1,
+                opp_move: GameMove::Scissors,
+            },
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::RevealMove {
+                game_id: 1,
+                host_move: GameMove::Rock,
+                salt: SALT.to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER.to_string(),
+                amount: vec![luna(10), tnt(200)],
+            })
+        );
+    }
+
+    #[test]
+    fn house_fee_test() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            fee_bps: 250,
+            fee_collector: Some("house".to_string()),
+            ..default_instantiate_msg(Some(USER))
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info(USER, &[]), msg).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[]),
+            ExecuteMsg::UpdateConfig {
+                fee_bps: Some(0),
+                fee_collector: None,
+                randomness_provider: None,
+                max_house_bet_bps: None,
+                allowed_denoms: None,
+            },
+        );
+        match res {
+            Err(ContractError::Admin(AdminError::NotAdmin {})) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::UpdateConfig {
+                fee_bps: Some(5_000),
+                fee_collector: None,
+                randomness_provider: None,
+                max_house_bet_bps: None,
+                allowed_denoms: None,
+            },
+        );
+        match res {
+            Err(ContractError::InvalidFee { max: _ }) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let _res = start_game(deps.as_mut()).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(
+                OPPONENT,
+                &[Coin {
+                    amount: AMOUNT,
+                    denom: DENOM.to_string(),
+                }],
+            ),
+            ExecuteMsg::OpponentResponse {
+                game_id: 1,
+                opp_move: GameMove::Scissors,
+            },
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::RevealMove {
+                game_id: 1,
+                host_move: GameMove::Rock,
+                salt: SALT.to_string(),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(res.attributes[5].value, "5TNT ");
+        assert_eq!(res.attributes[6].value, "house");
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER.to_string(),
+                amount: vec![Coin {
+                    amount: Uint128::new(195),
+                    denom: DENOM.to_string(),
+                }],
+            })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "house".to_string(),
+                amount: vec![Coin {
+                    amount: Uint128::new(5),
+                    denom: DENOM.to_string(),
+                }],
+            })
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let value: Config = from_binary(&res).unwrap();
+        assert_eq!(value.fee_bps, 250);
+    }
+
+    #[test]
+    fn player_stats_test() {
+        let mut deps = mock_dependencies();
+        let _res = instantiate_contract(deps.as_mut());
+
+        let _res = play_game(
+            &mut deps,
+            USER,
+            OPPONENT,
+            GameMove::Rock,
+            GameMove::Scissors,
+        );
+        let _res = play_game(
+            &mut deps,
+            "player3",
+            OPPONENT,
+            GameMove::Paper,
+            GameMove::Rock,
+        );
+        let _res = play_game(&mut deps, "player3", USER, GameMove::Rock, GameMove::Rock);
+        let _res = play_game(
+            &mut deps,
+            "player3",
+            USER,
+            GameMove::Scissors,
+            GameMove::Rock,
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetPlayerStats {
+                address: USER.to_string(),
+            },
+        )
+        .unwrap();
+        let value: PlayerStatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.wins, 2);
+        assert_eq!(value.losses, 0);
+        assert_eq!(value.ties, 1);
+        assert_eq!(value.total_wagered[0].amount, Uint128::new(300));
+        assert_eq!(value.total_won[0].amount, Uint128::new(500));
+
+        //players who never finished a game have an empty record
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetPlayerStats {
+                address: "nobody".to_string(),
+            },
+        )
+        .unwrap();
+        let value: PlayerStatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.wins, 0);
+        assert!(value.total_wagered.is_empty());
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetTopPlayers {
+                order_by: TopPlayersOrder::Wins,
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: GetTopPlayersResponse = from_binary(&res).unwrap();
+        let players: Vec<_> = value.players.iter().map(|p| p.address.as_str()).collect();
+        assert_eq!(players, vec![USER, "player3", OPPONENT]);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetTopPlayers {
+                order_by: TopPlayersOrder::Wins,
+                start_after: Some(USER.to_string()),
+                limit: Some(1),
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: GetTopPlayersResponse = from_binary(&res).unwrap();
+        assert_eq!(value.players.len(), 1);
+        assert_eq!(value.players[0].address, "player3");
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetTopPlayers {
+                order_by: TopPlayersOrder::NetWinnings {
+                    denom: DENOM.to_string(),
+                },
+                start_after: Some("player3".to_string()),
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: GetTopPlayersResponse = from_binary(&res).unwrap();
+        assert_eq!(value.players.len(), 1);
+        assert_eq!(value.players[0].address, OPPONENT);
+        assert_eq!(value.players[0].total_won[0].amount, Uint128::zero());
+
+        //from the bottom up the cursor is the lower bound
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetTopPlayers {
+                order_by: TopPlayersOrder::Wins,
+                start_after: Some(OPPONENT.to_string()),
+                limit: None,
+                order: Some(OrderBy::Ascending),
+            },
+        )
+        .unwrap();
+        let value: GetTopPlayersResponse = from_binary(&res).unwrap();
+        let players: Vec<_> = value.players.iter().map(|p| p.address.as_str()).collect();
+        assert_eq!(players, vec!["player3", USER]);
+    }
+
+    #[test]
+    fn rating_test() {
+        let mut deps = mock_dependencies();
+        let _res = instantiate_contract(deps.as_mut());
+
+        assert_eq!(expected_score(1200, 1200), 500);
+        assert_eq!(expected_score(1600, 1200), 909);
+        assert_eq!(expected_score(1200, 1600), 91);
+        assert_eq!(expected_score(3000, 1200), 990);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetRating {
+                address: USER.to_string(),
+            },
+        )
+        .unwrap();
+        let value: PlayerRating = from_binary(&res).unwrap();
+        assert_eq!(value.rating, 1200);
+        assert_eq!(value.games_played, 0);
+
+        //evenly matched players swap half the k-factor
+        let _res = play_game(
+            &mut deps,
+            USER,
+            OPPONENT,
+            GameMove::Rock,
+            GameMove::Scissors,
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetRatings {
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: GetRatingsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.ratings[0].address, USER);
+        assert_eq!(value.ratings[0].rating, 1216);
+        assert_eq!(value.ratings[1].address, OPPONENT);
+        assert_eq!(value.ratings[1].rating, 1184);
+
+        //the favourite gains less from beating the same player again
+        let _res = play_game(&mut deps, USER, OPPONENT, GameMove::Paper, GameMove::Rock);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetRatings {
+                start_after: Some(USER.to_string()),
+                limit: Some(1),
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: GetRatingsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.ratings.len(), 1);
+        assert_eq!(value.ratings[0].address, OPPONENT);
+        assert_eq!(value.ratings[0].rating, 1169);
+        assert_eq!(value.ratings[0].games_played, 2);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetRatings {
+                start_after: None,
+                limit: None,
+                order: Some(OrderBy::Ascending),
+            },
+        )
+        .unwrap();
+        let value: GetRatingsResponse = from_binary(&res).unwrap();
+        let players: Vec<_> = value.ratings.iter().map(|r| r.address.as_str()).collect();
+        assert_eq!(players, vec![OPPONENT, USER]);
+    }
+    #[test]
+    fn series_test() {
+        let mut deps = mock_dependencies();
+        let _res = instantiate_contract(deps.as_mut());
+        let wager = [Coin {
+            denom: DENOM.to_string(),
+            amount: AMOUNT,
+        }];
+
+        let start_series = |best_of| ExecuteMsg::StartGame {
+            opponent: Some(OPPONENT.to_string()),
+            host_move_commitment: hash_move(&GameMove::Rock, SALT),
+            timeout: None,
+            best_of: Some(best_of),
+            rule_set: None,
+        };
+        let respond = |game_id, opp_move| ExecuteMsg::OpponentResponse { game_id, opp_move };
+        let reveal = |game_id, host_move| ExecuteMsg::RevealMove {
+            game_id,
+            host_move,
+            salt: SALT.to_string(),
+        };
+        let commit = |game_id, host_move| ExecuteMsg::CommitMove {
+            game_id,
+            host_move_commitment: hash_move(&host_move, SALT),
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &wager),
+            start_series(2),
+        );
+        match res {
+            Err(ContractError::InvalidBestOf { max: _ }) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &wager),
+            start_series(3),
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &wager),
+            respond(1, GameMove::Scissors),
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            reveal(1, GameMove::Rock),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[4].value, "host_wins");
+        assert_eq!(res.attributes[5].value, "1");
+        assert!(res.messages.is_empty());
+
+        //the opponent has to wait for the host's next move
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[]),
+            respond(1, GameMove::Paper),
+        );
+        match res {
+            Err(ContractError::HostHasNotCommitted {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            commit(1, GameMove::Paper),
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &wager),
+            respond(1, GameMove::Paper),
+        );
+        match res {
+            Err(ContractError::WagerAlreadyEscrowed {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        //a tied round is replayed
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[]),
+            respond(1, GameMove::Paper),
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            reveal(1, GameMove::Paper),
+        )
+        .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetSeries { game_id: 1 },
+        )
+        .unwrap();
+        let value: SeriesResponse = from_binary(&res).unwrap();
+        assert_eq!(value.status, GameStatus::AwaitingCommitment);
+        assert_eq!(value.host_score, 1);
+        assert_eq!(value.opp_score, 0);
+        assert_eq!(value.rounds.len(), 2);
+        assert_eq!(value.rounds[1].opp_move, GameMove::Paper);
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            commit(1, GameMove::Scissors),
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[]),
+            respond(1, GameMove::Paper),
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            reveal(1, GameMove::Scissors),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[4].value, "host_wins");
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER.to_string(),
+                amount: vec![Coin {
+                    amount: Uint128::new(200),
+                    denom: DENOM.to_string(),
+                }],
+            })
+        );
+
+        //an opponent who stops responding partway through forfeits the series
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &wager),
+            start_series(3),
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &wager),
+            respond(2, GameMove::Rock),
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            reveal(2, GameMove::Rock),
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            commit(2, GameMove::Rock),
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env_after_timeout(),
+            mock_info(USER, &[]),
+            ExecuteMsg::ClaimTimeout { game_id: 2 },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[4].value, "host_wins");
+    }
+    #[test]
+    fn rule_set_test() {
+        let classic: Vec<String> = ["rock", "scissors", "paper"]
+            .iter()
+            .map(|m| m.to_string())
+            .collect();
+        assert_eq!(
+            get_game_result(&classic, &GameMove::Rock, &GameMove::Paper).unwrap(),
+            GameResult::OpponentWins
+        );
+        assert_eq!(
+            get_game_result(&classic, &GameMove::Scissors, &GameMove::Paper).unwrap(),
+            GameResult::HostWins
+        );
+        match get_game_result(&classic, &GameMove::Rock, &GameMove::Spock) {
+            Err(ContractError::InvalidMove {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let mut deps = mock_dependencies();
+        let msg = default_instantiate_msg(Some(USER));
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info(USER, &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetRuleSets {
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: GetRuleSetsResponse = from_binary(&res).unwrap();
+        let rpsls = value.rule_sets[1].moves.clone();
+        assert_eq!(
+            get_game_result(&rpsls, &GameMove::Spock, &GameMove::Scissors).unwrap(),
+            GameResult::HostWins
+        );
+        assert_eq!(
+            get_game_result(&rpsls, &GameMove::Spock, &GameMove::Lizard).unwrap(),
+            GameResult::OpponentWins
+        );
+
+        let register = |moves: &[&str]| ExecuteMsg::RegisterRuleSet {
+            name: "elements".to_string(),
+            moves: moves.iter().map(|m| m.to_string()).collect(),
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[]),
+            register(&["fire", "grass", "water"]),
+        );
+        match res {
+            Err(ContractError::Admin(AdminError::NotAdmin {})) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            register(&["fire", "grass", "water", "air"]),
+        );
+        match res {
+            Err(ContractError::InvalidRuleSet { max: _ }) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            register(&["fire", "grass", "water"]),
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            register(&["fire", "grass", "water"]),
+        );
+        match res {
+            Err(ContractError::RuleSetAlreadyExists {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::RegisterRuleSet {
+                name: "classic".to_string(),
+                moves: vec!["fire".to_string(), "grass".to_string(), "water".to_string()],
+            },
+        );
+        match res {
+            Err(ContractError::RuleSetAlreadyExists {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let wager = [Coin {
+            denom: DENOM.to_string(),
+            amount: AMOUNT,
+        }];
+        let start_game = |name: &str| ExecuteMsg::StartGame {
+            opponent: Some(OPPONENT.to_string()),
+            host_move_commitment: hash_move(&GameMove::Custom("water".to_string()), SALT),
+            timeout: None,
+            best_of: None,
+            rule_set: Some(RuleSet::Custom {
+                name: name.to_string(),
+            }),
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &wager),
+            start_game("missing"),
+        );
+        match res {
+            Err(ContractError::RuleSetNotFound {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &wager),
+            start_game("elements"),
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &wager),
+            ExecuteMsg::OpponentResponse {
+                game_id: 1,
+                opp_move: GameMove::Rock,
+            },
+        );
+        match res {
+            Err(ContractError::InvalidMove {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &wager),
+            ExecuteMsg::OpponentResponse {
+                game_id: 1,
+                opp_move: GameMove::Custom("fire".to_string()),
+            },
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::RevealMove {
+                game_id: 1,
+                host_move: GameMove::Custom("water".to_string()),
+                salt: SALT.to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[4].value, "host_wins");
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetRuleSets {
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: GetRuleSetsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.rule_sets.len(), 3);
+        assert_eq!(
+            value.rule_sets[1].rule_set,
+            RuleSet::Custom {
+                name: "elements".to_string()
+            }
+        );
+
+        //built-in and registered rule sets share one order and count against the limit
+        let page = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                    start_after: Option<&str>,
+                    order| {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetRuleSets {
+                    start_after: start_after.map(String::from),
+                    limit: Some(2),
+                    order,
+                },
+            )
+            .unwrap();
+            let value: GetRuleSetsResponse = from_binary(&res).unwrap();
+            let names = value
+                .rule_sets
+                .iter()
+                .map(|rule_set| events::rule_set_to_string(&rule_set.rule_set).to_string())
+                .collect::<Vec<_>>();
+            (names, value.next_start_after)
+        };
+        assert_eq!(
+            page(&deps, None, None),
+            (
+                vec!["classic".to_string(), "elements".to_string()],
+                Some("elements".to_string())
+            )
+        );
+        assert_eq!(
+            page(&deps, Some("elements"), None),
+            (vec!["rpsls".to_string()], None)
+        );
+        assert_eq!(
+            page(&deps, None, Some(OrderBy::Descending)),
+            (
+                vec!["rpsls".to_string(), "elements".to_string()],
+                Some("elements".to_string())
+            )
+        );
+        assert_eq!(
+            page(&deps, Some("elements"), Some(OrderBy::Descending)),
+            (vec!["classic".to_string()], None)
+        );
+    }
+    #[test]
+    fn tournament_test() {
+        let mut deps = mock_dependencies();
+        let _res = instantiate_contract(deps.as_mut());
+        let entry_fee = Coin {
+            denom: DENOM.to_string(),
+            amount: AMOUNT,
+        };
+        let funds = vec![entry_fee.clone()];
+        let join = ExecuteMsg::JoinTournament { tournament_id: 1 };
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("organizer", &[]),
+            ExecuteMsg::CreateTournament {
+                entry_fee: entry_fee.clone(),
+                max_participants: 4,
+                payouts_bps: vec![7_000, 2_000],
+                rule_set: None,
+                timeout: None,
+            },
+        );
+        match res {
+            Err(ContractError::InvalidPayouts {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("organizer", &[]),
+            ExecuteMsg::CreateTournament {
+                entry_fee: entry_fee.clone(),
+                max_participants: 4,
+                payouts_bps: vec![7_000, 3_000],
+                rule_set: None,
+                timeout: None,
+            },
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            join.clone(),
+        );
+        match res {
+            Err(ContractError::InsufficientWagerAmount {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        for player in [USER, OPPONENT, "player3"] {
+            let _res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(player, &funds),
+                join.clone(),
+            )
+            .unwrap();
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &funds),
+            join.clone(),
+        );
+        match res {
+            Err(ContractError::AlreadyRegistered {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::StartTournament { tournament_id: 1 },
+        );
+        match res {
+            Err(ContractError::NotOrganizer {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("organizer", &[]),
+            ExecuteMsg::StartTournament { tournament_id: 1 },
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("player4", &funds),
+            join,
+        );
+        match res {
+            Err(ContractError::TournamentStarted {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        //the odd player out gets a bye
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetTournament { tournament_id: 1 },
+        )
+        .unwrap();
+        let value: Tournament = from_binary(&res).unwrap();
+        assert_eq!(value.status, TournamentStatus::InProgress);
+        assert_eq!(value.matches, vec![1]);
+        assert_eq!(value.advanced, vec![Addr::unchecked("player3")]);
+
+        //bracket matches replay ties
+        play_match(&mut deps, 1, USER, OPPONENT, GameMove::Rock, GameMove::Rock);
+        play_match(
+            &mut deps,
+            1,
+            USER,
+            OPPONENT,
+            GameMove::Paper,
+            GameMove::Rock,
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetTournament { tournament_id: 1 },
+        )
+        .unwrap();
+        let value: Tournament = from_binary(&res).unwrap();
+        assert_eq!(value.round, 2);
+        assert_eq!(value.matches, vec![2]);
+        assert_eq!(value.eliminated[0], vec![Addr::unchecked(OPPONENT)]);
+
+        //the final is settled by timeout when USER never responds
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("player3", &[]),
+            ExecuteMsg::CommitMove {
+                game_id: 2,
+                host_move_commitment: hash_move(&GameMove::Rock, SALT),
+            },
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env_after_timeout(),
+            mock_info(OPPONENT, &[]),
+            ExecuteMsg::ClaimTimeout { game_id: 2 },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[4].value, "host_wins");
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "player3".to_string(),
+                amount: vec![Coin {
+                    amount: Uint128::new(210),
+                    denom: DENOM.to_string(),
+                }],
+            })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER.to_string(),
+                amount: vec![Coin {
+                    amount: Uint128::new(90),
+                    denom: DENOM.to_string(),
+                }],
+            })
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetTournament { tournament_id: 1 },
+        )
+        .unwrap();
+        let value: Tournament = from_binary(&res).unwrap();
+        assert_eq!(value.status, TournamentStatus::Finished);
+        assert_eq!(value.champion, Some(Addr::unchecked("player3")));
+    }
+    #[test]
+    fn season_test() {
+        let mut deps = mock_dependencies();
+        let msg = default_instantiate_msg(Some(USER));
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info(USER, &[]), msg).unwrap();
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::CreateSeason {
+                max_participants: 4,
+                payouts_bps: vec![6_000, 4_000],
+                rule_set: None,
+                timeout: None,
+            },
+        )
+        .unwrap();
+
+        for player in ["p1", "p2", "p3"] {
+            let _res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(player, &[]),
+                ExecuteMsg::JoinSeason { season_id: 1 },
+            )
+            .unwrap();
+        }
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(
+                "sponsor",
+                &[Coin {
+                    denom: DENOM.to_string(),
+                    amount: Uint128::new(300),
+                }],
+            ),
+            ExecuteMsg::FundSeason { season_id: 1 },
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("p1", &[]),
+            ExecuteMsg::StartSeason { season_id: 1 },
+        );
+        match res {
+            Err(ContractError::Admin(AdminError::NotAdmin {})) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::StartSeason { season_id: 1 },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[2].value, "3");
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("p4", &[]),
+            ExecuteMsg::JoinSeason { season_id: 1 },
+        );
+        match res {
+            Err(ContractError::SeasonStarted {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        play_match(&mut deps, 1, "p1", "p2", GameMove::Rock, GameMove::Scissors);
+        play_match(&mut deps, 2, "p1", "p3", GameMove::Rock, GameMove::Rock);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::FinalizeSeason { season_id: 1 },
+        );
+        match res {
+            Err(ContractError::SeasonNotOver {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        play_match(&mut deps, 3, "p2", "p3", GameMove::Paper, GameMove::Rock);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::FinalizeSeason { season_id: 1 },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[2].value, "p1");
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "p1".to_string(),
+                amount: vec![Coin {
+                    amount: Uint128::new(180),
+                    denom: DENOM.to_string(),
+                }],
+            })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "p2".to_string(),
+                amount: vec![Coin {
+                    amount: Uint128::new(120),
+                    denom: DENOM.to_string(),
+                }],
+            })
+        );
+        assert_eq!(res.messages.len(), 2);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetSeason { season_id: 1 },
+        )
+        .unwrap();
+        let value: Season = from_binary(&res).unwrap();
+        assert_eq!(value.status, SeasonStatus::Finished);
+        assert_eq!(value.standings[0].points, 4);
+        assert_eq!(value.standings[1].points, 3);
+        assert_eq!(value.standings[2].player, "p3");
+        assert_eq!(value.standings[2].ties, 1);
+    }
+    #[test]
+    fn house_test() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            randomness_provider: Some("beacon".to_string()),
+            max_house_bet_bps: Some(1_000),
+            ..default_instantiate_msg(Some(USER))
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info(USER, &[]), msg).unwrap();
+
+        let bet = |amount: u128| {
+            [Coin {
+                denom: DENOM.to_string(),
+                amount: Uint128::new(amount),
+            }]
+        };
+        let play = ExecuteMsg::PlayHouse {
+            player_move: GameMove::Paper,
+            rule_set: None,
+        };
+
+        //an empty bankroll can't cover any bet
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &bet(1)),
+            play.clone(),
+        );
+        match res {
+            Err(ContractError::BetTooLarge { max_bps: 1_000 }) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &bet(1_000)),
+            ExecuteMsg::FundHouse {},
+        );
+        match res {
+            Err(ContractError::Admin(AdminError::NotAdmin {})) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &bet(1_000)),
+            ExecuteMsg::FundHouse {},
+        )
+        .unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetHouse {}).unwrap();
+        let value: HouseResponse = from_binary(&res).unwrap();
+        assert_eq!(value.max_bet[0].amount, Uint128::new(100));
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &bet(101)),
+            play.clone(),
+        );
+        match res {
+            Err(ContractError::BetTooLarge { max_bps: _ }) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &bet(100)),
+            play,
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "beacon".to_string(),
+                msg: to_binary(&RandomnessProviderMsg::GetNextRandomness {
+                    job_id: "house-1".to_string(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        //the house's matching stake is reserved while the game is open
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetHouse {}).unwrap();
+        let value: HouseResponse = from_binary(&res).unwrap();
+        assert_eq!(value.bankroll[0].amount, Uint128::new(900));
+
+        let deliver = ExecuteMsg::ReceiveRandomness {
+            job_id: "house-1".to_string(),
+            randomness: Binary::from(vec![0; 32]),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[]),
+            deliver.clone(),
+        );
+        match res {
+            Err(ContractError::NotRandomnessProvider {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("beacon", &[]), deliver).unwrap();
+        assert_eq!(res.attributes[3].value, "rock");
+        assert_eq!(res.attributes[4].value, "player_wins");
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: OPPONENT.to_string(),
+                amount: vec![Coin {
+                    amount: Uint128::new(200),
+                    denom: DENOM.to_string(),
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn pause_test() {
+        let mut deps = mock_dependencies();
+        let msg = default_instantiate_msg(Some("admin"));
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info(USER, &[]), msg).unwrap();
+
+        let _res = start_game(deps.as_mut()).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::Pause {},
+        );
+        match res {
+            Err(ContractError::Admin(AdminError::NotAdmin {})) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap();
+        assert_eq!(res.attributes[0].value, "pause");
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let config: Config = from_binary(&res).unwrap();
+        assert!(config.paused);
+
+        //no new games or responses while paused
+        match start_game(deps.as_mut()) {
+            Err(ContractError::Paused {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let wager = [Coin {
+            denom: DENOM.to_string(),
+            amount: AMOUNT,
+        }];
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &wager),
+            ExecuteMsg::OpponentResponse {
+                game_id: 1,
+                opp_move: GameMove::Paper,
+            },
+        );
+        match res {
+            Err(ContractError::Paused {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        //but the host can still get their wager back
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::CancelGame { game_id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER.to_string(),
+                amount: wager.to_vec(),
+            })
+        );
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::Unpause {},
+        )
+        .unwrap();
+        let _res = start_game(deps.as_mut()).unwrap();
+    }
+
+    #[test]
+    fn update_admin_test() {
+        let mut deps = mock_dependencies();
+        let msg = default_instantiate_msg(Some(USER));
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info(USER, &[]), msg).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::UpdateConfig {
+                fee_bps: Some(100),
+                fee_collector: None,
+                randomness_provider: Some("beacon".to_string()),
+                max_house_bet_bps: None,
+                allowed_denoms: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("execute", "update_config"),
+                attr("admin", USER),
+                attr("fee_bps", "100"),
+                attr("randomness_provider", "beacon"),
+            ]
+        );
+
+        //house mode can be switched off again
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[]),
+            ExecuteMsg::ClearRandomnessProvider {},
+        );
+        match res {
+            Err(ContractError::Admin(AdminError::NotAdmin {})) => {}
+            _ => panic!("Should error here"),
+        }
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::ClearRandomnessProvider {},
+        )
+        .unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let value: Config = from_binary(&res).unwrap();
+        assert_eq!(value.randomness_provider, None);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[Coin::new(10, DENOM)]),
+            ExecuteMsg::PlayHouse {
+                player_move: GameMove::Rock,
+                rule_set: None,
+            },
+        );
+        match res {
+            Err(ContractError::HouseDisabled {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[]),
+            ExecuteMsg::UpdateAdmin {
+                admin: Some(OPPONENT.to_string()),
+            },
+        );
+        match res {
+            Err(ContractError::Admin(AdminError::NotAdmin {})) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::UpdateAdmin {
+                admin: Some(OPPONENT.to_string()),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[2], attr("new_admin", OPPONENT));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Admin {}).unwrap();
+        let value: AdminResponse = from_binary(&res).unwrap();
+        assert_eq!(value.admin, Some(OPPONENT.to_string()));
+
+        //the old admin is locked out
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::Pause {},
+        );
+        match res {
+            Err(ContractError::Admin(AdminError::NotAdmin {})) => {}
+            _ => panic!("Should error here"),
+        }
+
+        //nobody could unpause the contract after renouncing
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[]),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[]),
+            ExecuteMsg::UpdateAdmin { admin: None },
+        );
+        match res {
+            Err(ContractError::RenounceWhilePaused {}) => {}
+            _ => panic!("Should error here"),
+        }
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[]),
+            ExecuteMsg::Unpause {},
+        )
+        .unwrap();
+
+        //nor withdraw the house bankroll
+        let funds = vec![Coin::new(100, DENOM)];
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &funds),
+            ExecuteMsg::FundHouse {},
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[]),
+            ExecuteMsg::UpdateAdmin { admin: None },
+        );
+        match res {
+            Err(ContractError::RenounceWithBankroll {}) => {}
+            _ => panic!("Should error here"),
+        }
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[]),
+            ExecuteMsg::WithdrawHouse { amount: funds },
+        )
+        .unwrap();
+
+        //renouncing leaves the contract without an admin
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[]),
+            ExecuteMsg::UpdateAdmin { admin: None },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[2], attr("new_admin", "None"));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Admin {}).unwrap();
+        let value: AdminResponse = from_binary(&res).unwrap();
+        assert_eq!(value.admin, None);
+    }
+
+    #[test]
+    fn migrate_test() {
+        let mut deps = mock_dependencies();
+        let _res = instantiate_contract(deps.as_mut());
+
+        //same version is a no-op
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(res.attributes.len(), 3);
+
+        set_contract_version(
+            deps.as_mut().storage,
+            "crates.io:rock-paper-scissors",
+            "99.0.0",
+        )
+        .unwrap();
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+        match res {
+            Err(ContractError::CannotDowngrade { version }) => assert_eq!(version, "99.0.0"),
+            _ => panic!("Should error here"),
+        }
+
+        set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "0.1.0").unwrap();
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+        match res {
+            Err(ContractError::WrongContract { name }) => assert_eq!(name, "crates.io:cw20-base"),
+            _ => panic!("Should error here"),
+        }
+    }
+
+    #[test]
+    fn wager_limits_test() {
+        let mut deps = mock_dependencies();
+        let msg = default_instantiate_msg(Some(USER));
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info(USER, &[]), msg).unwrap();
+
+        let update = |allowed_denoms: Vec<DenomLimit>| ExecuteMsg::UpdateConfig {
+            fee_bps: None,
+            fee_collector: None,
+            randomness_provider: None,
+            max_house_bet_bps: None,
+            allowed_denoms: Some(allowed_denoms),
+        };
+        let limit = |denom: &str, min: u128, max: Option<u128>| DenomLimit {
+            denom: denom.to_string(),
+            min: Uint128::new(min),
+            max: max.map(Uint128::new),
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            update(vec![limit(DENOM, 200, Some(100))]),
+        );
+        match res {
+            Err(ContractError::InvalidDenomLimits {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            update(vec![limit(DENOM, 10, Some(100)), limit("LUNA", 1, None)]),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes[2],
+            attr("allowed_denoms", "LUNA:1-,TNT:10-100")
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetAllowedDenoms {}).unwrap();
+        let value: AllowedDenomsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.denoms,
+            vec![limit("LUNA", 1, None), limit(DENOM, 10, Some(100))]
+        );
+
+        let start = |deps: DepsMut, wager: Coin| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(USER, &[wager]),
+                ExecuteMsg::StartGame {
+                    opponent: Some(OPPONENT.to_string()),
+                    host_move_commitment: hash_move(&GameMove::Rock, SALT),
+                    timeout: None,
+                    best_of: None,
+                    rule_set: None,
+                },
+            )
+        };
+
+        match start(deps.as_mut(), Coin::new(5, DENOM)) {
+            Err(ContractError::WagerTooSmall { denom, min }) => {
+                assert_eq!((denom.as_str(), min), (DENOM, Uint128::new(10)))
+            }
+            _ => panic!("Should error here"),
+        }
+        match start(deps.as_mut(), Coin::new(101, DENOM)) {
+            Err(ContractError::WagerTooLarge { denom, max }) => {
+                assert_eq!((denom.as_str(), max), (DENOM, Uint128::new(100)))
+            }
+            _ => panic!("Should error here"),
+        }
+        match start(deps.as_mut(), Coin::new(50, "ibc/27394FB0")) {
+            Err(ContractError::DenomNotAllowed { denom }) => assert_eq!(denom, "ibc/27394FB0"),
+            _ => panic!("Should error here"),
+        }
+
+        let _res = start(deps.as_mut(), Coin::new(100, DENOM)).unwrap();
+        let _res = start(deps.as_mut(), Coin::new(1_000_000, "LUNA")).unwrap();
+
+        //clearing the list accepts any denom again
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            update(vec![]),
+        )
+        .unwrap();
+        let _res = start(deps.as_mut(), Coin::new(1, "ibc/27394FB0")).unwrap();
+    }
+
+    #[test]
+    fn history_test() {
+        let mut deps = mock_dependencies();
+        let _res = instantiate_contract(deps.as_mut());
+
+        let _res = play_game(&mut deps, USER, OPPONENT, GameMove::Rock, GameMove::Paper);
+        let _res = play_game(&mut deps, OPPONENT, "third", GameMove::Rock, GameMove::Rock);
+        let _res = play_game(&mut deps, "third", USER, GameMove::Paper, GameMove::Rock);
+
+        let history = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, msg: QueryMsg| {
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            let value: GetHistoryResponse = from_binary(&res).unwrap();
+            value
+                .games
+                .iter()
+                .map(|settled| settled.game.id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            history(
+                &deps,
+                QueryMsg::GetRecentGames {
+                    start_after: None,
+                    limit: None,
+                    order: None,
+                }
+            ),
+            vec![3, 2, 1]
+        );
+        assert_eq!(
+            history(
+                &deps,
+                QueryMsg::GetHistoryByPlayer {
+                    player: USER.to_string(),
+                    start_after: None,
+                    limit: Some(1),
+                    order: None,
+                }
+            ),
+            vec![3]
+        );
+        assert_eq!(
+            history(
+                &deps,
+                QueryMsg::GetHistoryByPlayer {
+                    player: USER.to_string(),
+                    start_after: Some(3),
+                    limit: None,
+                    order: None,
+                }
+            ),
+            vec![1]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetHistoryByPlayer {
+                player: OPPONENT.to_string(),
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: GetHistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(value.games.len(), 2);
+        let settled = &value.games[1];
+        assert_eq!(settled.height, mock_env().block.height);
+        assert_eq!(settled.time, mock_env().block.time);
+        assert_eq!(settled.game.host_move, Some(GameMove::Rock));
+        assert_eq!(settled.game.opp_move, Some(GameMove::Paper));
+        assert_eq!(settled.game.result, Some(GameResult::OpponentWins));
+
+        //settled games are gone from the open games
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGame { game_id: 1 });
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn pagination_test() {
+        let mut deps = mock_dependencies();
+        let _res = instantiate_contract(deps.as_mut());
+
+        //three open challenges and a game against a named opponent
+        for (opponent, amount) in [(None, 20), (None, 10), (None, 20), (Some(OPPONENT), 50)] {
+            let _res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(USER, &[Coin::new(amount, DENOM)]),
+                ExecuteMsg::StartGame {
+                    opponent: opponent.map(String::from),
+                    host_move_commitment: hash_move(&GameMove::Rock, SALT),
+                    timeout: None,
+                    best_of: None,
+                    rule_set: None,
+                },
+            )
+            .unwrap();
+        }
+
+        let page = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, msg: QueryMsg| {
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            let value: GetGamesResponse = from_binary(&res).unwrap();
+            (
+                value.games.iter().map(|game| game.id).collect::<Vec<_>>(),
+                value.next_start_after,
+            )
+        };
+        let by_host = |start_after, order| QueryMsg::GetGamesByHost {
+            host: USER.to_string(),
+            start_after,
+            limit: Some(3),
+            order,
+        };
+
+        assert_eq!(page(&deps, by_host(None, None)), (vec![1, 2, 3], Some(3)));
+        assert_eq!(page(&deps, by_host(Some(3), None)), (vec![4], None));
+        assert_eq!(
+            page(&deps, by_host(None, Some(OrderBy::Descending))),
+            (vec![4, 3, 2], Some(2))
+        );
+        assert_eq!(
+            page(&deps, by_host(Some(2), Some(OrderBy::Descending))),
+            (vec![1], None)
+        );
+
+        //open challenges resume after the cursor's wager amount
+        let open = |start_after, order| QueryMsg::GetOpenChallenges {
+            denom: DENOM.to_string(),
+            min_amount: None,
+            max_amount: None,
+            start_after,
+            limit: Some(2),
+            order,
+        };
+        let open_page = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, msg: QueryMsg| {
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            let value: OpenChallengesResponse = from_binary(&res).unwrap();
+            (
+                value.games.iter().map(|game| game.id).collect::<Vec<_>>(),
+                value.next_start_after,
+            )
+        };
+        let cursor = OpenChallengeCursor {
+            amount: Uint128::new(20),
+            game_id: 1,
+        };
+        assert_eq!(
+            open_page(&deps, open(None, None)),
+            (vec![2, 1], Some(cursor.clone()))
+        );
+        assert_eq!(
+            open_page(&deps, open(Some(cursor.clone()), None)),
+            (vec![3], None)
+        );
+        assert_eq!(
+            open_page(&deps, open(None, Some(OrderBy::Descending))),
+            (vec![3, 1], Some(cursor.clone()))
+        );
+        assert_eq!(
+            open_page(&deps, open(Some(cursor.clone()), Some(OrderBy::Descending))),
+            (vec![2], None)
+        );
+
+        //the cursor still works once its game is accepted
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[Coin::new(20, DENOM)]),
+            ExecuteMsg::OpponentResponse {
+                game_id: 1,
+                opp_move: GameMove::Paper,
+            },
+        )
+        .unwrap();
+        assert_eq!(open_page(&deps, open(Some(cursor), None)), (vec![3], None));
+
+        //limits are capped
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGamesByHost {
+                host: USER.to_string(),
+                start_after: None,
+                limit: Some(1_000),
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: GetGamesResponse = from_binary(&res).unwrap();
+        assert_eq!(value.games.len(), 4);
+        assert_eq!(value.next_start_after, None);
+    }
+
+    #[test]
+    fn pending_challenges_test() {
+        let mut deps = mock_dependencies();
+        let _res = instantiate_contract(deps.as_mut());
+
+        let wager = [Coin::new(10, DENOM)];
+        let challenge = |opponent: Option<&str>, timeout| ExecuteMsg::StartGame {
+            opponent: opponent.map(String::from),
+            host_move_commitment: hash_move(&GameMove::Rock, SALT),
+            timeout,
+            best_of: None,
+            rule_set: None,
+        };
+
+        //waiting on the opponent to respond
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &wager),
+            challenge(Some(OPPONENT), None),
+        )
+        .unwrap();
+        //waiting on the opponent to reveal, as host
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &wager),
+            challenge(Some(USER), None),
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &wager),
+            ExecuteMsg::OpponentResponse {
+                game_id: 2,
+                opp_move: GameMove::Paper,
+            },
+        )
+        .unwrap();
+        //open to anyone, so nobody's inbox
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &wager),
+            challenge(None, None),
+        )
+        .unwrap();
+        //expires before the query below
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("third", &wager),
+            challenge(Some(OPPONENT), Some(Duration::Time(60))),
+        )
+        .unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(120);
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetPendingChallenges {
+                player: OPPONENT.to_string(),
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: PendingChallengesResponse = from_binary(&res).unwrap();
+        assert_eq!(value.next_start_after, None);
+        assert_eq!(
+            value
+                .challenges
+                .iter()
+                .map(|c| (c.game_id, c.status.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, GameStatus::AwaitingOpponent),
+                (2, GameStatus::AwaitingReveal)
+            ]
+        );
+        assert_eq!(value.challenges[0].host, Addr::unchecked(USER));
+        assert_eq!(value.challenges[0].wager, Balance::from(wager.to_vec()));
+        assert_eq!(
+            value.challenges[0].expires,
+            Expiration::AtTime(
+                mock_env()
+                    .block
+                    .time
+                    .plus_seconds(DEFAULT_GAME_TIMEOUT_SECS)
+            )
+        );
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetPendingChallenges {
+                player: OPPONENT.to_string(),
+                start_after: None,
+                limit: Some(1),
+                order: Some(OrderBy::Descending),
+            },
+        )
+        .unwrap();
+        let value: PendingChallengesResponse = from_binary(&res).unwrap();
+        assert_eq!(value.challenges[0].game_id, 2);
+        assert_eq!(value.next_start_after, Some(2));
+
+        //the user has nothing to do until the opponent reveals
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::GetPendingChallenges {
+                player: USER.to_string(),
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: PendingChallengesResponse = from_binary(&res).unwrap();
+        assert!(value.challenges.is_empty());
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetGamesByOpponent {
+                opponent: OPPONENT.to_string(),
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: GetGamesResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.games.iter().map(|game| game.id).collect::<Vec<_>>(),
+            vec![1, 4]
+        );
+    }
+
+    #[test]
+    fn events_test() {
+        let mut deps = mock_dependencies();
+        let _res = instantiate_contract(deps.as_mut());
+        let wager = [Coin {
+            denom: DENOM.to_string(),
+            amount: AMOUNT,
+        }];
+        let expires = Expiration::AtTime(
+            mock_env()
+                .block
+                .time
+                .plus_seconds(DEFAULT_GAME_TIMEOUT_SECS),
+        )
+        .to_string();
+
+        let res = start_game(deps.as_mut()).unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new(events::GAME_STARTED).add_attributes(vec![
+                attr("game_id", "1"),
+                attr("mode", "player"),
+                attr("host", USER),
+                attr("opponent", OPPONENT),
+                attr("wager", "100TNT"),
+                attr("commitment", hash_move(&GameMove::Rock, SALT)),
+                attr("rule_set", "classic"),
+                attr("best_of", "1"),
+                attr("expires", &expires),
+            ])]
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::CancelGame { game_id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new(events::GAME_CANCELLED).add_attributes(vec![
+                attr("game_id", "1"),
+                attr("host", USER),
+                attr("opponent", OPPONENT),
+                attr("refund", "100TNT"),
+                attr("reason", "cancelled"),
+            ])]
+        );
+
+        //scores only count in a series and no fee is taken, so the fee keys are left out
+        let res = play_game(&mut deps, USER, OPPONENT, GameMove::Rock, GameMove::Paper);
+        assert_eq!(
+            res.events,
+            vec![Event::new(events::GAME_SETTLED).add_attributes(vec![
+                attr("game_id", "2"),
+                attr("host", USER),
+                attr("opponent", OPPONENT),
+                attr("host_move", GameMove::Rock.as_str()),
+                attr("opp_move", GameMove::Paper.as_str()),
+                attr("result", "opponent_wins"),
+                attr("winner", OPPONENT),
+                attr("host_score", "0"),
+                attr("opp_score", "0"),
+                attr("opp_payout", "200TNT"),
+            ])]
+        );
+
+        //every turn of a series has its own event
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &wager),
+            ExecuteMsg::StartGame {
+                opponent: None,
+                host_move_commitment: hash_move(&GameMove::Rock, SALT),
+                timeout: None,
+                best_of: Some(3),
+                rule_set: None,
+            },
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &wager),
+            ExecuteMsg::OpponentResponse {
+                game_id: 3,
+                opp_move: GameMove::Scissors,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new(events::GAME_RESPONDED).add_attributes(vec![
+                attr("game_id", "3"),
+                attr("host", USER),
+                attr("opponent", OPPONENT),
+                attr("opp_move", GameMove::Scissors.as_str()),
+                attr("opp_wager", "100TNT"),
+                attr("round", "1"),
+                attr("expires", &expires),
+            ])]
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::RevealMove {
+                game_id: 3,
+                host_move: GameMove::Rock,
+                salt: SALT.to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new(events::ROUND_SETTLED).add_attributes(vec![
+                attr("game_id", "3"),
+                attr("round", "1"),
+                attr("host_move", GameMove::Rock.as_str()),
+                attr("opp_move", GameMove::Scissors.as_str()),
+                attr("result", "host_wins"),
+                attr("host_score", "1"),
+                attr("opp_score", "0"),
+                attr("expires", &expires),
+            ])]
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::CommitMove {
+                game_id: 3,
+                host_move_commitment: hash_move(&GameMove::Paper, SALT),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new(events::MOVE_COMMITTED).add_attributes(vec![
+                attr("game_id", "3"),
+                attr("host", USER),
+                attr("opponent", OPPONENT),
+                attr("commitment", hash_move(&GameMove::Paper, SALT)),
+                attr("round", "2"),
+                attr("expires", &expires),
+            ])]
+        );
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[]),
+            ExecuteMsg::OpponentResponse {
+                game_id: 3,
+                opp_move: GameMove::Rock,
+            },
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::RevealMove {
+                game_id: 3,
+                host_move: GameMove::Paper,
+                salt: SALT.to_string(),
+            },
+        )
+        .unwrap();
+        //the deciding round has no deadline after it
+        assert_eq!(
+            res.events[0],
+            Event::new(events::ROUND_SETTLED).add_attributes(vec![
+                attr("game_id", "3"),
+                attr("round", "2"),
+                attr("host_move", GameMove::Paper.as_str()),
+                attr("opp_move", GameMove::Rock.as_str()),
+                attr("result", "host_wins"),
+                attr("host_score", "2"),
+                attr("opp_score", "0"),
+            ])
+        );
+        assert_eq!(res.events[1].ty, events::GAME_SETTLED);
+
+        //league matches are announced when the season starts
+        let mut deps = mock_dependencies();
+        let msg = default_instantiate_msg(Some(USER));
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info(USER, &[]), msg).unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::CreateSeason {
+                max_participants: 3,
+                payouts_bps: vec![10_000],
+                rule_set: None,
+                timeout: None,
+            },
+        )
+        .unwrap();
+        for player in ["p1", "p2", "p3"] {
+            let _res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(player, &[]),
+                ExecuteMsg::JoinSeason { season_id: 1 },
+            )
+            .unwrap();
+        }
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::StartSeason { season_id: 1 },
+        )
+        .unwrap();
+        assert_eq!(res.events.len(), 3);
+        assert_eq!(
+            res.events[2],
+            Event::new(events::GAME_STARTED).add_attributes(vec![
+                attr("game_id", "3"),
+                attr("mode", "season"),
+                attr("host", "p2"),
+                attr("opponent", "p3"),
+                attr("rule_set", "classic"),
+                attr("best_of", "1"),
+                attr("expires", &expires),
+                attr("season_id", "1"),
+            ])
+        );
+    }
+
+    #[test]
+    fn schema_test() {
+        let api = generate_api! {
+            instantiate: InstantiateMsg,
+            execute: ExecuteMsg,
+            query: QueryMsg,
+            migrate: MigrateMsg,
+        }
+        .render();
+        assert!(
+            api.to_string().unwrap() + "\n" == include_str!("../schema/rock-paper-scissors.json"),
+            "schema/ is stale, regenerate it with `cargo schema`"
+        );
+
+        let receive_msg = serde_json::to_string_pretty(&schema_for!(ReceiveMsg)).unwrap();
+        assert!(
+            receive_msg + "\n" == include_str!("../schema/receive_msg.json"),
+            "schema/ is stale, regenerate it with `cargo schema`"
+        );
+    }
+
+    #[test]
+    fn pause_timeout_test() {
+        let mut deps = mock_dependencies();
+        let msg = default_instantiate_msg(Some("admin"));
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info(USER, &[]), msg).unwrap();
+        let wager = [Coin {
+            denom: DENOM.to_string(),
+            amount: AMOUNT,
+        }];
+
+        //the host wins the first round of a series and commits to the second
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &wager),
+            ExecuteMsg::StartGame {
+                opponent: Some(OPPONENT.to_string()),
+                host_move_commitment: hash_move(&GameMove::Rock, SALT),
+                timeout: None,
+                best_of: Some(3),
+                rule_set: None,
+            },
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &wager),
+            ExecuteMsg::OpponentResponse {
+                game_id: 1,
+                opp_move: GameMove::Scissors,
+            },
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::RevealMove {
+                game_id: 1,
+                host_move: GameMove::Rock,
+                salt: SALT.to_string(),
+            },
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::CommitMove {
+                game_id: 1,
+                host_move_commitment: hash_move(&GameMove::Paper, SALT),
+            },
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::CreateTournament {
+                entry_fee: wager[0].clone(),
+                max_participants: 4,
+                payouts_bps: vec![10_000],
+                rule_set: None,
+                timeout: None,
+            },
+        )
+        .unwrap();
+
+        //the pause outlasts the opponent's timeout
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(60 * 60);
+        let _res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("admin", &[]),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(2 * DEFAULT_GAME_TIMEOUT_SECS);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(USER, &[]),
+            ExecuteMsg::ClaimTimeout { game_id: 1 },
+        );
+        match res {
+            Err(ContractError::GameNotExpired {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        //organizers can't seed new matches while paused
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(USER, &[]),
+            ExecuteMsg::StartTournament { tournament_id: 1 },
+        );
+        match res {
+            Err(ContractError::Paused {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin", &[]),
+            ExecuteMsg::Unpause {},
+        )
+        .unwrap();
+
+        //the opponent gets back the time the pause took from them
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(USER, &[]),
+            ExecuteMsg::ClaimTimeout { game_id: 1 },
+        );
+        match res {
+            Err(ContractError::GameNotExpired {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetPendingChallenges {
+                player: OPPONENT.to_string(),
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: PendingChallengesResponse = from_binary(&res).unwrap();
+        let paused_secs = 2 * DEFAULT_GAME_TIMEOUT_SECS - 60 * 60;
+        let expires = Expiration::AtTime(
+            mock_env()
+                .block
+                .time
+                .plus_seconds(DEFAULT_GAME_TIMEOUT_SECS + paused_secs),
+        );
+        assert_eq!(value.challenges[0].expires, expires);
+
+        //game queries report the same deadline
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetGame { game_id: 1 }).unwrap();
+        let value: GameState = from_binary(&res).unwrap();
+        assert_eq!(value.expires, expires);
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetGamesByOpponent {
+                opponent: OPPONENT.to_string(),
+                start_after: None,
+                limit: None,
+                order: None,
+            },
+        )
+        .unwrap();
+        let value: GetGamesResponse = from_binary(&res).unwrap();
+        assert_eq!(value.games[0].expires, expires);
+
+        let _res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(OPPONENT, &[]),
+            ExecuteMsg::OpponentResponse {
+                game_id: 1,
+                opp_move: GameMove::Rock,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn ambiguous_commitment_test() {
+        let mut deps = mock_dependencies();
+        let msg = default_instantiate_msg(Some(USER));
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info(USER, &[]), msg).unwrap();
+
+        //"rocks" + "x" and "rock" + "sx" are the same bytes without the length prefix
+        let rock = GameMove::Custom("rock".to_string());
+        let rocks = GameMove::Custom("rocks".to_string());
+        assert_ne!(hash_move(&rocks, "x"), hash_move(&rock, "sx"));
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::RegisterRuleSet {
+                name: "prefixes".to_string(),
+                moves: vec!["rock".to_string(), "rocks".to_string(), "paper".to_string()],
+            },
+        )
+        .unwrap();
+
+        let wager = [Coin {
+            denom: DENOM.to_string(),
+            amount: AMOUNT,
+        }];
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &wager),
+            ExecuteMsg::StartGame {
+                opponent: Some(OPPONENT.to_string()),
+                host_move_commitment: hash_move(&rocks, "x"),
+                timeout: None,
+                best_of: None,
+                rule_set: Some(RuleSet::Custom {
+                    name: "prefixes".to_string(),
+                }),
+            },
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &wager),
+            ExecuteMsg::OpponentResponse {
+                game_id: 1,
+                opp_move: GameMove::Custom("paper".to_string()),
+            },
+        )
+        .unwrap();
+
+        //the host can't switch to the other move after seeing the opponent's
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::RevealMove {
+                game_id: 1,
+                host_move: rock,
+                salt: "sx".to_string(),
+            },
+        );
+        match res {
+            Err(ContractError::InvalidReveal {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &[]),
+            ExecuteMsg::RevealMove {
+                game_id: 1,
+                host_move: rocks,
+                salt: "x".to_string(),
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn house_timeout_test() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            randomness_provider: Some("beacon".to_string()),
+            max_house_bet_bps: Some(1_000),
+            ..default_instantiate_msg(Some(USER))
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info(USER, &[]), msg).unwrap();
+
+        let bet = |amount: u128| {
+            vec![Coin {
+                denom: DENOM.to_string(),
+                amount: Uint128::new(amount),
+            }]
+        };
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &bet(1_000)),
+            ExecuteMsg::FundHouse {},
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &bet(100)),
+            ExecuteMsg::PlayHouse {
+                player_move: GameMove::Paper,
+                rule_set: None,
+            },
+        )
+        .unwrap();
+
+        //the beacon gets a game's timeout to answer
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &[]),
+            ExecuteMsg::ClaimTimeout { game_id: 1 },
+        );
+        match res {
+            Err(ContractError::GameNotExpired {}) => {}
+            _ => panic!("Should error here"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            env_after_timeout(),
+            mock_info(OPPONENT, &[]),
+            ExecuteMsg::ClaimTimeout { game_id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: OPPONENT.to_string(),
+                amount: bet(100),
+            })
+        );
+        assert_eq!(res.events[0].ty, events::GAME_CANCELLED);
+
+        //the house's reserve is back in the bankroll
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetHouse {}).unwrap();
+        let value: HouseResponse = from_binary(&res).unwrap();
+        assert_eq!(value.bankroll, bet(1_000));
+
+        //randomness arriving afterwards can't settle the game again
+        let res = execute(
+            deps.as_mut(),
+            env_after_timeout(),
+            mock_info("beacon", &[]),
+            ExecuteMsg::ReceiveRandomness {
+                job_id: "house-1".to_string(),
+                randomness: Binary::from(vec![0; 32]),
+            },
+        );
+        match res {
+            Err(ContractError::GameNotFound {}) => {}
+            _ => panic!("Should error here"),
+        }
+    }
+
+    #[test]
+    fn scan_limit_test() {
+        let mut deps = mock_dependencies();
+        let _res = instantiate_contract(deps.as_mut());
+
+        //a page of games against someone else hides the game both queries look for
+        let wager = [Coin::new(10, DENOM)];
+        let challenge = |opponent: &str| ExecuteMsg::StartGame {
+            opponent: Some(opponent.to_string()),
+            host_move_commitment: hash_move(&GameMove::Rock, SALT),
+            timeout: None,
+            best_of: None,
+            rule_set: None,
+        };
+        for _ in 0..100 {
+            let _res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(USER, &wager),
+                challenge("third"),
+            )
+            .unwrap();
+        }
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER, &wager),
+            challenge(OPPONENT),
+        )
+        .unwrap();
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OPPONENT, &wager),
+            challenge(USER),
+        )
+        .unwrap();
+
+        let by_host_and_opp = |start_after| QueryMsg::GetGameByHostAndOpponent {
+            host: USER.to_string(),
+            opponent: OPPONENT.to_string(),
+            start_after,
+            limit: None,
+            order: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), by_host_and_opp(None)).unwrap();
+        let value: GetGamesResponse = from_binary(&res).unwrap();
+        assert!(value.games.is_empty());
+        assert_eq!(value.next_start_after, Some(100));
+
+        let res = query(deps.as_ref(), mock_env(), by_host_and_opp(Some(100))).unwrap();
+        let value: GetGamesResponse = from_binary(&res).unwrap();
+        assert_eq!(value.games[0].id, 101);
+        assert_eq!(value.next_start_after, None);
+
+        //the challenge from the opponent lies past where the host side stopped
+        let pending = |start_after| QueryMsg::GetPendingChallenges {
+            player: USER.to_string(),
+            start_after,
+            limit: None,
+            order: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), pending(None)).unwrap();
+        let value: PendingChallengesResponse = from_binary(&res).unwrap();
+        assert!(value.challenges.is_empty());
+        assert_eq!(value.next_start_after, Some(100));
+
+        let res = query(deps.as_ref(), mock_env(), pending(Some(100))).unwrap();
+        let value: PendingChallengesResponse = from_binary(&res).unwrap();
+        assert_eq!(value.challenges.len(), 1);
+        assert_eq!(value.challenges[0].game_id, 102);
+        assert_eq!(value.next_start_after, None);
     }
 }